@@ -4,19 +4,101 @@ use crate::observer::PrivateMessageRequest;
 use crate::plugins::KVMap;
 use crate::socketlib::SocketConn;
 use crate::types::notifies::ClientBasicInfo;
-use crate::types::{QueryResult, SafeUserState};
+use crate::types::{ChannelWithLimits, Client, QueryResult, SafeUserState};
 use crate::{AUTO_CHANNEL_NICKNAME_OVERRIDE, DEFAULT_AUTO_CHANNEL_NICKNAME};
 use anyhow::anyhow;
 use log::{debug, error, info, trace, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
 use std::time::Duration;
-use tap::TapFallible;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
+
+const CHANNEL_CREATION_WAIT: Duration = Duration::from_millis(500);
+/// Wait before next reconnect attempt after connection lost
+pub(crate) const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+// (Virtual server unique id, client database id) which channel is being created, shared by
+// all auto channel staffs. Never locked across `.await`, so std mutex is fine.
+static CREATING_CHANNELS: LazyLock<Mutex<HashSet<(String, i64)>>> = LazyLock::new(Default::default);
+
+/// Held while creating channel for a client, removed from [`CREATING_CHANNELS`] on drop.
+struct ChannelCreationLock((String, i64));
+
+impl ChannelCreationLock {
+    fn try_acquire(server_id: &str, client_database_id: i64) -> Option<Self> {
+        let key = (server_id.to_string(), client_database_id);
+        let inserted = CREATING_CHANNELS.lock().unwrap().insert(key.clone());
+        // Construct lazily, dropping an unused lock would release the holder's entry
+        inserted.then(|| Self(key))
+    }
+}
+
+impl Drop for ChannelCreationLock {
+    fn drop(&mut self) {
+        // Poisoned mutex still holds valid set, entry must be removed anyway
+        CREATING_CHANNELS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.0);
+    }
+}
 
 pub enum AutoChannelEvent {
-    Update(ClientBasicInfo),
-    DeleteChannel(i64, String),
-    ShouldRefresh,
-    Terminate,
+    Update(ClientBasicInfo, Instant),
+    DeleteChannel(i64, String, Instant),
+    ChannelDeleted(i64, Instant),
+    ShouldRefresh(Instant),
+    Terminate(Instant),
+}
+
+impl AutoChannelEvent {
+    fn created_at(&self) -> Instant {
+        match self {
+            Self::Update(_, created_at)
+            | Self::DeleteChannel(_, _, created_at)
+            | Self::ChannelDeleted(_, created_at)
+            | Self::ShouldRefresh(created_at)
+            | Self::Terminate(created_at) => *created_at,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stats {
+    iterations: u64,
+    errors: u64,
+    channels_created: u64,
+    clients_moved: u64,
+    channels_deleted: u64,
+}
+
+impl Stats {
+    fn emit(&self) {
+        metrics::gauge!("autochannel_iterations").set(self.iterations as f64);
+        metrics::gauge!("autochannel_errors").set(self.errors as f64);
+        metrics::gauge!("autochannel_channels_created").set(self.channels_created as f64);
+        metrics::gauge!("autochannel_clients_moved").set(self.clients_moved as f64);
+        metrics::gauge!("autochannel_channels_deleted").set(self.channels_deleted as f64);
+    }
+}
+
+kstool_helper_generator::oneshot_helper! {
+    pub enum StatRequestEvent {
+        #[ret(Stats)]
+        Query,
+    }
+}
+
+/// Answer [`StatRequestEvent`] with the latest stats published by [`auto_channel_staff`].
+pub async fn serve_stats(mut receiver: StatRequestEventReceiver, stats: watch::Receiver<Stats>) {
+    while let Some(event) = receiver.recv().await {
+        match event {
+            StatRequestEvent::Query(sender) => {
+                sender.send(*stats.borrow()).ok();
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -27,7 +109,7 @@ pub struct AutoChannelInstance {
 
 impl AutoChannelInstance {
     pub async fn send_terminate(&self) -> anyhow::Result<()> {
-        self.send_signal(AutoChannelEvent::Terminate)
+        self.send_signal(AutoChannelEvent::Terminate(Instant::now()))
             .await
             .map(|_| ())
     }
@@ -44,7 +126,16 @@ impl AutoChannelInstance {
     }
 
     pub async fn send_delete(&self, user_id: i64, uid: String) -> anyhow::Result<bool> {
-        self.send_signal(AutoChannelEvent::DeleteChannel(user_id, uid))
+        self.send_signal(AutoChannelEvent::DeleteChannel(
+            user_id,
+            uid,
+            Instant::now(),
+        ))
+        .await
+    }
+
+    pub async fn send_channel_deleted(&self, channel_id: i64) -> anyhow::Result<bool> {
+        self.send_signal(AutoChannelEvent::ChannelDeleted(channel_id, Instant::now()))
             .await
     }
 
@@ -53,10 +144,12 @@ impl AutoChannelInstance {
             return Ok(false);
         }
         if !self.channel_ids.iter().any(|id| id == &view.channel_id()) {
-            self.send_signal(AutoChannelEvent::ShouldRefresh).await?;
+            self.send_signal(AutoChannelEvent::ShouldRefresh(Instant::now()))
+                .await?;
             return Ok(false);
         }
-        self.send_signal(AutoChannelEvent::Update(view)).await
+        self.send_signal(AutoChannelEvent::Update(view, Instant::now()))
+            .await
     }
 
     pub fn new(channel_ids: Vec<i64>, sender: Option<mpsc::Sender<AutoChannelEvent>>) -> Self {
@@ -81,48 +174,255 @@ pub async fn mute_porter_function(
         .await
         .map_err(|e| anyhow!("Unable query clients: {e:?}"))?
     {
-        if client.client_is_user()
-            && client.channel_id() == mute_porter.monitor_channel()
-            && !mute_porter.check_whitelist(client.client_database_id())
+        if !client.client_is_user()
+            || client.channel_id() != mute_porter.monitor_channel()
+            || mute_porter.check_whitelist(client.client_database_id())
+        {
+            continue;
+        }
+        match conn
+            .move_client_if_muted(client.client_id(), mute_porter.target_channel())
+            .await
+        {
+            Ok(true) => info!(
+                "[{thread_id}] Moved {} to {}",
+                client.client_id(),
+                mute_porter.target_channel()
+            ),
+            Ok(false) => {}
+            Err(e) => error!(
+                "[{thread_id}] Unable move client {} to channel {}: {e:?}",
+                client.client_id(),
+                mute_porter.target_channel(),
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Query client must stay in `channel_id`, see [`SocketConn::send_channel_message`].
+async fn announce_move(
+    conn: &mut SocketConn,
+    channel_id: i64,
+    nickname: &str,
+    created: bool,
+    thread_id: &str,
+) {
+    let message = if created {
+        format!("A new channel has been created for {nickname}, they have been moved into it.")
+    } else {
+        format!("{nickname} has been moved into their channel.")
+    };
+    conn.send_channel_message(channel_id, &message)
+        .await
+        .inspect_err(|e| error!("[{thread_id}] Got error while send announcement: {e:?}"))
+        .ok();
+}
+
+/// Family client limit applies to `channel_id`, as (channel id the limit set on, limit). Walk
+/// up parents while limit is inherited, return `None` if unlimited or inherited from server
+/// default, which is not checked.
+async fn effective_family_limit(
+    conn: &mut SocketConn,
+    channel_id: i64,
+) -> QueryResult<Option<(i64, i64)>> {
+    let mut channel_id = channel_id;
+    while let Some(info) = conn.query_channel_info(channel_id).await? {
+        if let Some(limit) = info.family_clients_limit() {
+            return Ok(Some((channel_id, limit)));
+        }
+        if !info.channel_flag_max_family_clients_inherited() || info.pid() == 0 {
+            break;
+        }
+        channel_id = info.pid();
+    }
+    Ok(None)
+}
+
+/// Try `base_name`, then `base_name (2)`, `base_name (3)`... while name is in use (error 771).
+async fn create_channel_with_unique_name(
+    conn: &mut SocketConn,
+    base_name: &str,
+    pid: i64,
+    max_attempts: usize,
+) -> anyhow::Result<i64> {
+    for attempt in 1..=max_attempts.max(1) {
+        let name = if attempt == 1 {
+            base_name.to_string()
+        } else {
+            format!("{base_name} ({attempt})")
+        };
+        match conn.create_channel(&name, pid).await {
+            Ok(Some(ret)) => return Ok(ret.cid()),
+            Ok(None) => return Err(anyhow!("Create {name:?} channel return nothing")),
+            Err(e) if e.code() == 771 => continue,
+            Err(e) => return Err(anyhow!("Got error while create {name:?} channel: {e:?}")),
+        }
+    }
+    Err(anyhow!(
+        "Unable find unused name for {base_name:?} after {max_attempts} attempts"
+    ))
+}
+
+fn build_redis_key(client_database_id: i64, server_id: &str, channel_id: i64) -> String {
+    format!(
+        "ts_autochannel_{client_database_id}_{server_id}_{pid}",
+        pid = channel_id
+    )
+}
+
+/// Reverse of [`build_redis_key`], auto channel id => key which stores it.
+fn build_owner_key(server_id: &str, channel_id: i64) -> String {
+    format!("ts_channelowner_{server_id}_{channel_id}")
+}
+
+/// Store `channel_id` under `key`, and `key` under its reverse key.
+async fn remember_channel(
+    kv_map: &mut dyn KVMap,
+    server_id: &str,
+    key: String,
+    channel_id: i64,
+) -> anyhow::Result<()> {
+    kv_map
+        .set(build_owner_key(server_id, channel_id), key.clone())
+        .await?;
+    kv_map.set(key, channel_id.to_string()).await?;
+    Ok(())
+}
+
+/// Remove `key` with its reverse key, return `true` if `key` existed.
+async fn forget_channel(
+    kv_map: &mut dyn KVMap,
+    server_id: &str,
+    key: String,
+) -> anyhow::Result<bool> {
+    let Some(value) = kv_map.get(key.clone()).await? else {
+        return Ok(false);
+    };
+    if let Ok(channel_id) = value.parse() {
+        kv_map
+            .delete(build_owner_key(server_id, channel_id))
+            .await?;
+    }
+    kv_map.delete(key).await?;
+    Ok(true)
+}
+
+/// Remove KV entry point to deleted `channel_id` through its reverse key, return `true` if
+/// removed. Channels not created by auto channel have no reverse key and are ignored.
+async fn forget_deleted_channel(
+    kv_map: &mut dyn KVMap,
+    server_id: &str,
+    channel_id: i64,
+) -> anyhow::Result<bool> {
+    let owner_key = build_owner_key(server_id, channel_id);
+    let Some(key) = kv_map.get(owner_key.clone()).await? else {
+        return Ok(false);
+    };
+    kv_map.delete(owner_key).await?;
+    // Entry may be replaced or removed since channel created
+    if kv_map
+        .get(key.clone())
+        .await?
+        .is_some_and(|value| value == channel_id.to_string())
+    {
+        kv_map.delete(key).await?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn transfer_channel_ownership(
+    conn: &mut SocketConn,
+    clients: &[Client],
+    channel_owners: &mut HashMap<i64, (i64, i64)>,
+    kv_map: &mut dyn KVMap,
+    private_message_sender: &mpsc::Sender<PrivateMessageRequest>,
+    server_id: &str,
+    privilege_group: i64,
+    thread_id: &str,
+) -> anyhow::Result<()> {
+    for (channel_id, (owner, parent_id)) in channel_owners.iter_mut() {
+        let members = clients
+            .iter()
+            .filter(|client| client.channel_id() == *channel_id && client.client_is_user())
+            .collect::<Vec<_>>();
+        if members
+            .iter()
+            .any(|client| client.client_database_id() == *owner)
         {
-            if let Some(true) = conn
+            continue;
+        }
+
+        let mut new_owner: Option<(&Client, i64)> = None;
+        for client in members {
+            let Some(info) = conn
                 .query_client_info(client.client_id())
                 .await
-                .inspect_err(|e| error!("[{thread_id}] Unable query client information: {e:?}",))
+                .inspect_err(|e| error!("[{thread_id}] Unable query client information: {e:?}"))
                 .ok()
                 .flatten()
-                .map(|r| r.is_client_muted())
-            {
-                conn.move_client(client.client_id(), mute_porter.target_channel())
-                    .await
-                    .inspect_err(|e| {
-                        error!(
-                            "[{thread_id}] Unable move client {} to channel {}: {e:?}",
-                            client.client_id(),
-                            mute_porter.target_channel(),
-                        )
-                    })
-                    .map(|_| {
-                        info!(
-                            "[{thread_id}] Moved {} to {}",
-                            client.client_id(),
-                            mute_porter.target_channel()
-                        )
-                    })
-                    .ok();
+            else {
+                continue;
+            };
+            if new_owner.is_none_or(|(_, connected)| info.connection_connected_time() > connected) {
+                new_owner.replace((client, info.connection_connected_time()));
             }
         }
+        let Some((client, _)) = new_owner else {
+            continue;
+        };
+
+        if let Err(e) = conn
+            .set_client_channel_group(client.client_database_id(), *channel_id, privilege_group)
+            .await
+        {
+            error!("[{thread_id}] Unable transfer channel {channel_id} ownership: {e:?}");
+            continue;
+        }
+
+        kv_map
+            .delete(build_redis_key(*owner, server_id, *parent_id))
+            .await?;
+        let key = build_redis_key(client.client_database_id(), server_id, *parent_id);
+        // Keep new owner's own channel if they already have one
+        if kv_map.get(key.clone()).await?.is_none() {
+            remember_channel(kv_map, server_id, key, *channel_id).await?;
+        }
+
+        info!(
+            "[{thread_id}] Transfer channel {channel_id} from {owner} to {}({})",
+            client.client_nickname(),
+            client.client_database_id()
+        );
+        *owner = client.client_database_id();
+
+        private_message_sender
+            .send(PrivateMessageRequest::Message(
+                client.client_id(),
+                "The previous owner left, you are now the owner of this channel.".into(),
+            ))
+            .await
+            .inspect_err(|_| warn!("[{thread_id}] Send message request fail"))
+            .ok();
     }
     Ok(())
 }
 
-fn build_redis_key(client_database_id: i64, server_id: &str, channel_id: i64) -> String {
-    format!(
-        "ts_autochannel_{client_database_id}_{server_id}_{pid}",
-        pid = channel_id
+/// Sleep for `duration` but still accept terminate, return `true` if should terminate. Other
+/// events are dropped, caller should refresh afterward.
+async fn wait_or_terminate(
+    receiver: &mut mpsc::Receiver<AutoChannelEvent>,
+    duration: Duration,
+) -> bool {
+    matches!(
+        tokio::time::timeout(duration, receiver.recv()).await,
+        Ok(Some(AutoChannelEvent::Terminate(_)) | None)
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn auto_channel_staff(
     mut conn: SocketConn,
     mut receiver: mpsc::Receiver<AutoChannelEvent>,
@@ -131,92 +431,227 @@ pub async fn auto_channel_staff(
     thread_id: String,
     mut kv_map: Box<dyn KVMap>,
     user_map: SafeUserState,
+    stats_sender: watch::Sender<Stats>,
 ) -> anyhow::Result<()> {
+    let mut stats = Stats::default();
     let monitor_channels = config.server().channels();
     let privilege_group = config.server().privilege_group_id();
     let channel_permissions = config.channel_permissions();
     let moved_message = config.message().move_to_channel();
+    let channel_full_message = config.message().channel_full();
+    let mut announce_channel = config.auto_channel().announce_in_channel();
+    let move_cooldown = config.auto_channel().move_cooldown();
+    // Client database id which was told their channel is full, until they get moved or leave
+    let mut full_notified: HashSet<i64> = HashSet::new();
+    // Client database id => (last moved time, skip already logged)
+    let mut last_moved: HashMap<i64, (Instant, bool)> = HashMap::new();
+    let transfer_ownership = config.auto_channel().transfer_ownership_on_leave();
+    let max_name_attempts = config.auto_channel().max_name_attempts();
+    // Auto channel id => (owner client database id, parent channel id)
+    let mut channel_owners: HashMap<i64, (i64, i64)> = HashMap::new();
     conn.change_nickname(
         AUTO_CHANNEL_NICKNAME_OVERRIDE.get_or_init(|| DEFAULT_AUTO_CHANNEL_NICKNAME.to_string()),
     )
     .await
     .map_err(|e| anyhow!("Got error while change nickname: {e:?}"))?;
 
-    let who_am_i = conn
+    let client_database_id = conn
         .who_am_i()
         .await
-        .map_err(|e| anyhow!("Whoami failed: {e:?}"))?;
+        .map_err(|e| anyhow!("Whoami failed: {e:?}"))?
+        .client_database_id();
 
     let server_info = conn
         .query_server_info()
         .await
         .map_err(|e| anyhow!("Query server info error: {e:?}"))?;
 
-    info!("[{thread_id}] Connected: {}", who_am_i.client_id());
+    let mut self_client_id = conn
+        .cached_client_id()
+        .ok_or_else(|| anyhow!("Whoami does not return client id"))?;
+
+    // Park in announce channel once, it is restored by reconnect. 770: already member of channel
+    if let Some(channel_id) = announce_channel
+        && let Err(e) = conn.set_own_channel(channel_id, None).await
+        && e.code() != 770
+    {
+        error!(
+            "[{thread_id}] Unable move self to announce channel {channel_id}, announcement disabled: {e:?}"
+        );
+        announce_channel = None;
+    }
+
+    info!("[{thread_id}] Connected: {self_client_id}");
     debug!("[{thread_id}] Monitor: {}", monitor_channels.len());
 
+    let startup_limits = conn
+        .query_channels_with_limits()
+        .await
+        .inspect_err(|e| error!("[{thread_id}] Got error while query channel limits: {e:?}"))
+        .unwrap_or_default();
+    for channel_id in &monitor_channels {
+        let (limited_channel, limit) = match effective_family_limit(&mut conn, *channel_id).await {
+            Ok(Some(limit)) => limit,
+            Ok(None) => {
+                debug!("[{thread_id}] Channel {channel_id} family clients limit not checked");
+                continue;
+            }
+            Err(e) => {
+                error!("[{thread_id}] Unable query channel {channel_id} info: {e:?}");
+                continue;
+            }
+        };
+        let Some(channel) = startup_limits
+            .iter()
+            .find(|channel| channel.cid() == limited_channel)
+        else {
+            continue;
+        };
+        if channel.total_clients_family() >= limit {
+            warn!(
+                "[{thread_id}] Channel {:?}({limited_channel}) family clients limit {limit} reached, auto channel under {channel_id} is unable to accept new clients",
+                channel.channel_name()
+            );
+        } else {
+            info!(
+                "[{thread_id}] Channel {:?}({limited_channel}) limits family clients to {limit}, {} in use",
+                channel.channel_name(),
+                channel.total_clients_family()
+            );
+        }
+    }
+
     let mut should_refresh = false;
     let mut skip_sleep = true;
     loop {
+        // Publish at loop head since iteration may end by any `continue`
+        stats_sender.send_replace(stats);
+        stats.emit();
+        stats.iterations += 1;
+        if conn.connection_lost() {
+            warn!("[{thread_id}] Connection lost, reconnecting");
+            if let Err(e) = conn.reconnect().await {
+                error!("[{thread_id}] Reconnect failed: {e:?}");
+                stats.errors += 1;
+                if wait_or_terminate(&mut receiver, RECONNECT_INTERVAL).await {
+                    break;
+                }
+                continue;
+            }
+            self_client_id = conn.cached_client_id().unwrap_or(self_client_id);
+            info!("[{thread_id}] Reconnected: {self_client_id}");
+            // Events may be missed while disconnected
+            skip_sleep = true;
+        }
         if !skip_sleep {
             //std::thread::sleep(Duration::from_millis(interval));
             match tokio::time::timeout(Duration::from_secs(30), receiver.recv()).await {
-                Ok(Some(event)) => match event {
-                    AutoChannelEvent::Terminate => break,
-                    AutoChannelEvent::Update(view) => {
-                        if view.client_id() == who_am_i.client_id() {
-                            continue;
+                Ok(Some(event)) => {
+                    metrics::histogram!("autochannel_event_latency_ms")
+                        .record((Instant::now() - event.created_at()).as_millis() as f64);
+                    match event {
+                        AutoChannelEvent::Terminate(_) => break,
+                        AutoChannelEvent::Update(view, _) => {
+                            if view.client_id() == self_client_id {
+                                continue;
+                            }
                         }
-                    }
-                    AutoChannelEvent::DeleteChannel(client_id, uid) => {
-                        let result = conn
-                            .client_get_database_id_from_uid(&uid)
-                            .await
-                            .map_err(|e| anyhow!("Got error while query {uid} {e:?}",))?;
-                        for channel_id in &monitor_channels {
-                            let key = build_redis_key(
-                                result.client_database_id(),
-                                server_info.virtual_server_unique_identifier(),
-                                *channel_id,
-                            );
+                        AutoChannelEvent::DeleteChannel(client_id, uid, _) => {
+                            let result = conn
+                                .client_get_database_id_from_uid(&uid)
+                                .await
+                                .map_err(|e| anyhow!("Got error while query {uid} {e:?}",))?;
+                            for channel_id in &monitor_channels {
+                                let key = build_redis_key(
+                                    result.client_database_id(),
+                                    server_info.virtual_server_unique_identifier(),
+                                    *channel_id,
+                                );
 
-                            kv_map
-                                .delete(key)
+                                // `delete` doesn't tell if key existed, only count stored entries
+                                match forget_channel(
+                                    kv_map.as_mut(),
+                                    server_info.virtual_server_unique_identifier(),
+                                    key,
+                                )
+                                .await
+                                {
+                                    Ok(false) => {}
+                                    Ok(true) => {
+                                        trace!("[{thread_id}] Deleted");
+                                        stats.channels_deleted += 1;
+                                    }
+                                    Err(e) => {
+                                        error!(
+                                            "[{thread_id}] Got error while delete from redis: {e:?}"
+                                        );
+                                        stats.errors += 1;
+                                    }
+                                }
+                            }
+                            private_message_sender
+                                .send(PrivateMessageRequest::Message(
+                                    client_id,
+                                    "Received.".into(),
+                                ))
                                 .await
-                                .tap_ok(|_| trace!("[{thread_id}] Deleted"))
-                                .inspect_err(|e| {
-                                    error!("[{thread_id}] Got error while delete from redis: {e:?}")
+                                .inspect_err(|_| {
+                                    error!("[{thread_id}] Got error in request send message")
                                 })
                                 .ok();
                         }
-                        private_message_sender
-                            .send(PrivateMessageRequest::Message(
-                                client_id,
-                                "Received.".into(),
-                            ))
+                        AutoChannelEvent::ChannelDeleted(channel_id, _) => {
+                            channel_owners.remove(&channel_id);
+                            match forget_deleted_channel(
+                                kv_map.as_mut(),
+                                server_info.virtual_server_unique_identifier(),
+                                channel_id,
+                            )
                             .await
-                            .inspect_err(|_| {
-                                error!("[{thread_id}] Got error in request send message")
-                            })
-                            .ok();
-                    }
-                    AutoChannelEvent::ShouldRefresh => {
-                        should_refresh = true;
+                            {
+                                Ok(false) => {}
+                                Ok(true) => {
+                                    trace!(
+                                        "[{thread_id}] Channel {channel_id} deleted, entry removed"
+                                    );
+                                    stats.channels_deleted += 1;
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "[{thread_id}] Got error while remove deleted channel {channel_id}: {e:?}"
+                                    );
+                                    stats.errors += 1;
+                                }
+                            }
+                            continue;
+                        }
+                        AutoChannelEvent::ShouldRefresh(_) => {
+                            should_refresh = true;
+                        }
                     }
-                },
+                }
                 Ok(None) => {
                     error!("[{thread_id}] Channel closed!");
                     break;
                 }
                 Err(_) => {
-                    conn.who_am_i()
-                        .await
-                        .inspect_err(|e| {
-                            error!("[{thread_id}] Got error while doing keep alive {e:?}")
-                        })
-                        .ok();
+                    if let Err(e) = conn.who_am_i().await {
+                        error!("[{thread_id}] Got error while doing keep alive {e:?}");
+                        if let Some(delay) = e.flood_ban_delay() {
+                            warn!("[{thread_id}] Banned by anti-flood, retry after {delay:?}");
+                            if wait_or_terminate(&mut receiver, delay).await {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
                     if config.mute_porter().enable() {
-                        mute_porter_function(&mut conn, config.mute_porter(), &thread_id).await?;
+                        mute_porter_function(&mut conn, config.mute_porter(), &thread_id)
+                            .await
+                            .inspect_err(|e| {
+                                error!("[{thread_id}] Got error in mute porter: {e:?}")
+                            })
+                            .ok();
                     }
                     if !should_refresh {
                         continue;
@@ -226,21 +661,78 @@ pub async fn auto_channel_staff(
         } else {
             skip_sleep = false;
         }
-        let Ok(clients) = conn
-            .query_clients()
+        let clients = match conn.query_clients().await {
+            Ok(clients) => clients,
+            Err(e) => {
+                error!("[{thread_id}] Got error while query clients: {e:?}");
+                stats.errors += 1;
+                if let Some(delay) = e.flood_ban_delay() {
+                    warn!("[{thread_id}] Banned by anti-flood, retry after {delay:?}");
+                    if wait_or_terminate(&mut receiver, delay).await {
+                        break;
+                    }
+                }
+                continue;
+            }
+        };
+
+        if transfer_ownership
+            && let Err(e) = transfer_channel_ownership(
+                &mut conn,
+                &clients,
+                &mut channel_owners,
+                kv_map.as_mut(),
+                &private_message_sender,
+                server_info.virtual_server_unique_identifier(),
+                privilege_group,
+                &thread_id,
+            )
             .await
-            .inspect_err(|e| error!("[{thread_id}] Got error while query clients: {e:?}"))
-        else {
+        {
+            if !conn.connection_lost() {
+                return Err(e);
+            }
+            error!("[{thread_id}] Connection lost while transfer channel ownership: {e:?}");
             continue;
-        };
+        }
 
+        // Lazily queried when moving someone into an existing channel
+        let mut channel_limits: Option<Vec<ChannelWithLimits>> = None;
+
+        // Expired entries are useless, drop them so map doesn't grow with every client moved
+        last_moved.retain(|_, (moved_at, _)| moved_at.elapsed() < move_cooldown);
+
+        full_notified.retain(|client_database_id| {
+            clients.iter().any(|client| {
+                client.client_database_id() == *client_database_id
+                    && monitor_channels.contains(&client.channel_id())
+            })
+        });
+
+        // Some client is left in monitored channel, check again even no event arrived
+        let mut retry = false;
         'outer: for client in &clients {
-            if client.client_database_id() == who_am_i.client_database_id()
+            if client.client_database_id() == client_database_id
                 || !monitor_channels.iter().any(|v| *v == client.channel_id())
                 || client.client_type() == 1
             {
                 continue;
             }
+            if let Some((moved_at, logged)) = last_moved.get_mut(&client.client_database_id())
+                && moved_at.elapsed() < move_cooldown
+            {
+                if !*logged {
+                    warn!(
+                        "[{thread_id}] Skip {}({}), moved {:?} ago",
+                        client.client_nickname(),
+                        client.client_database_id(),
+                        moved_at.elapsed()
+                    );
+                    *logged = true;
+                }
+                retry = true;
+                continue;
+            }
             // TODO: May need add thread id
             let key = format!(
                 "ts_autochannel_{}_{server_id}_{pid}",
@@ -249,7 +741,7 @@ pub async fn auto_channel_staff(
                 pid = client.channel_id()
             );
 
-            let ret: Option<i64> = kv_map
+            let mut ret: Option<i64> = kv_map
                 .get(key.clone())
                 .await?
                 .map(|v| v.parse())
@@ -257,26 +749,45 @@ pub async fn auto_channel_staff(
                 .inspect_err(|e| error!("[{thread_id}] Unable to parse result: {e:?}"))
                 .ok()
                 .flatten();
+            let mut creation_lock = None;
+            if ret.is_none() {
+                creation_lock = ChannelCreationLock::try_acquire(
+                    server_info.virtual_server_unique_identifier(),
+                    client.client_database_id(),
+                );
+                if creation_lock.is_none() {
+                    // Another staff is creating channel for this client, wait for its KV write
+                    tokio::time::sleep(CHANNEL_CREATION_WAIT).await;
+                    ret = kv_map.get(key.clone()).await?.and_then(|v| v.parse().ok());
+                    if ret.is_none() {
+                        warn!(
+                            "[{thread_id}] Skip {}({}), channel creation in progress",
+                            client.client_nickname(),
+                            client.client_database_id()
+                        );
+                        retry = true;
+                        continue;
+                    }
+                }
+            }
             let create_new = ret.is_none();
             let target_channel = if create_new {
-                let mut name = format!("{}'s channel", client.client_nickname());
-                let channel_id = loop {
-                    let create_channel = match conn.create_channel(&name, client.channel_id()).await
-                    {
-                        Ok(Some(ret)) => ret.cid(),
-                        Err(e) => {
-                            if e.code() == 771 {
-                                name.push('1');
-                                continue;
-                            }
-                            error!("[{thread_id}] Got error while create {name:?} channel: {e:?}",);
-                            continue 'outer;
-                        }
-                        _ => unreachable!(),
-                    };
-
-                    break create_channel;
+                let channel_id = match create_channel_with_unique_name(
+                    &mut conn,
+                    &format!("{}'s channel", client.client_nickname()),
+                    client.channel_id(),
+                    max_name_attempts,
+                )
+                .await
+                {
+                    Ok(channel_id) => channel_id,
+                    Err(e) => {
+                        error!("[{thread_id}] {e:?}");
+                        stats.errors += 1;
+                        continue 'outer;
+                    }
                 };
+                stats.channels_created += 1;
 
                 conn.set_client_channel_group(
                     client.client_database_id(),
@@ -312,6 +823,41 @@ pub async fn auto_channel_staff(
                 ret.unwrap()
             };
 
+            if !create_new {
+                if channel_limits.is_none() {
+                    channel_limits = conn
+                        .query_channels_with_limits()
+                        .await
+                        .inspect_err(|e| {
+                            error!("[{thread_id}] Got error while query channel limits: {e:?}")
+                        })
+                        .ok();
+                }
+                if let Some(channel) = channel_limits
+                    .iter()
+                    .flatten()
+                    .find(|channel| channel.cid() == target_channel)
+                    && !channel.has_room()
+                {
+                    if full_notified.insert(client.client_database_id()) {
+                        warn!(
+                            "[{thread_id}] Channel {target_channel} is full, skip move {}",
+                            client.client_nickname()
+                        );
+                        private_message_sender
+                            .send(PrivateMessageRequest::Message(
+                                client.client_id(),
+                                channel_full_message.clone().into(),
+                            ))
+                            .await
+                            .inspect_err(|_| warn!("[{thread_id}] Send message request fail"))
+                            .ok();
+                    }
+                    retry = true;
+                    continue;
+                }
+            }
+
             if let Err(e) = conn.move_client(client.client_id(), target_channel).await {
                 if e.code() == 768 {
                     kv_map.delete(key.clone()).await?;
@@ -319,8 +865,29 @@ pub async fn auto_channel_staff(
                     continue;
                 }
                 error!("[{thread_id}] Got error while move client: {e:?}");
+                stats.errors += 1;
                 continue;
             };
+            stats.clients_moved += 1;
+            full_notified.remove(&client.client_database_id());
+            if let Some(channel) = channel_limits
+                .iter_mut()
+                .flatten()
+                .find(|channel| channel.cid() == target_channel)
+            {
+                channel.client_moved_in();
+            }
+
+            if !move_cooldown.is_zero() {
+                last_moved.insert(client.client_database_id(), (Instant::now(), false));
+            }
+
+            if transfer_ownership {
+                channel_owners.insert(
+                    target_channel,
+                    (client.client_database_id(), client.channel_id()),
+                );
+            }
 
             private_message_sender
                 .send(PrivateMessageRequest::Message(
@@ -332,10 +899,32 @@ pub async fn auto_channel_staff(
                 .ok();
 
             if create_new {
-                conn.move_client(who_am_i.client_id(), client.channel_id())
-                    .await
-                    .map_err(|e| anyhow!("Unable move self out of channel. {e:?}"))?;
-                kv_map.set(key.clone(), target_channel.to_string()).await?;
+                // Creator is put into the new channel, go back to where announcements are sent
+                conn.move_client(
+                    self_client_id,
+                    announce_channel.unwrap_or(client.channel_id()),
+                )
+                .await
+                .map_err(|e| anyhow!("Unable move self out of channel. {e:?}"))?;
+                remember_channel(
+                    kv_map.as_mut(),
+                    server_info.virtual_server_unique_identifier(),
+                    key.clone(),
+                    target_channel,
+                )
+                .await?;
+            }
+            drop(creation_lock);
+
+            if let Some(announce_channel) = announce_channel {
+                announce_move(
+                    &mut conn,
+                    announce_channel,
+                    client.client_nickname(),
+                    create_new,
+                    &thread_id,
+                )
+                .await;
             }
 
             info!(
@@ -343,6 +932,7 @@ pub async fn auto_channel_staff(
                 client.client_nickname(),
             );
         }
+        should_refresh = retry;
 
         if !user_map.enabled() {
             continue;
@@ -350,8 +940,75 @@ pub async fn auto_channel_staff(
         if let Ok(channels) = conn.query_channels().await {
             user_map.update(channels, clients).await;
         }
-        should_refresh = false;
     }
-    conn.logout().await?;
+    if !conn.connection_lost() {
+        conn.logout().await?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ChannelCreationLock, build_redis_key, forget_deleted_channel, remember_channel};
+    use crate::plugins::KVMap;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryMap(HashMap<String, String>);
+
+    #[async_trait::async_trait]
+    impl KVMap for MemoryMap {
+        async fn set(&mut self, key: String, value: String) -> anyhow::Result<Option<()>> {
+            Ok(self.0.insert(key, value).map(|_| ()))
+        }
+
+        async fn delete(&mut self, key: String) -> anyhow::Result<()> {
+            self.0.remove(&key);
+            Ok(())
+        }
+
+        async fn get(&mut self, key: String) -> anyhow::Result<Option<String>> {
+            Ok(self.0.get(&key).cloned())
+        }
+
+        async fn list_keys(&mut self, _pattern: &str) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_forget_deleted_channel() {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let mut kv = MemoryMap::default();
+                let key = build_redis_key(3, "server", 1);
+                remember_channel(&mut kv, "server", key.clone(), 10)
+                    .await
+                    .unwrap();
+                // Not created by auto channel
+                assert!(!forget_deleted_channel(&mut kv, "server", 11).await.unwrap());
+                assert!(forget_deleted_channel(&mut kv, "server", 10).await.unwrap());
+                assert!(kv.0.is_empty());
+
+                // Entry replaced by another channel is kept
+                remember_channel(&mut kv, "server", key.clone(), 10)
+                    .await
+                    .unwrap();
+                kv.set(key.clone(), "12".to_string()).await.unwrap();
+                assert!(!forget_deleted_channel(&mut kv, "server", 10).await.unwrap());
+                assert_eq!(kv.0.get(&key).map(String::as_str), Some("12"));
+            });
+    }
+
+    #[test]
+    fn test_channel_creation_lock() {
+        let lock = ChannelCreationLock::try_acquire("server-a", 42).unwrap();
+        assert!(ChannelCreationLock::try_acquire("server-a", 42).is_none());
+        // Database ids are per virtual server
+        assert!(ChannelCreationLock::try_acquire("server-b", 42).is_some());
+        drop(lock);
+        assert!(ChannelCreationLock::try_acquire("server-a", 42).is_some());
+    }
+}
@@ -4,6 +4,7 @@ pub mod config {
     use serde::Deserialize;
     use std::collections::HashMap;
     use std::fmt::Debug;
+    use std::time::Duration;
     use tokio::io::AsyncReadExt;
 
     use crate::plugins::{Backend, ForkConnection};
@@ -51,6 +52,13 @@ pub mod config {
         port: Option<u16>,
         user: String,
         password: String,
+        #[serde(alias = "flood-exempt")]
+        flood_exempt: Option<bool>,
+        #[serde(alias = "commands-per-second")]
+        commands_per_second: Option<f64>,
+        #[cfg(feature = "ssh")]
+        #[serde(default)]
+        ssh: bool,
     }
 
     impl RawQuery {
@@ -63,9 +71,18 @@ pub mod config {
         }
 
         pub fn port(&self) -> u16 {
+            #[cfg(feature = "ssh")]
+            if self.ssh {
+                return self.port.unwrap_or(10022);
+            }
             self.port.unwrap_or(10011)
         }
 
+        #[cfg(feature = "ssh")]
+        pub fn ssh(&self) -> bool {
+            self.ssh
+        }
+
         pub fn user(&self) -> &str {
             &self.user
         }
@@ -73,6 +90,14 @@ pub mod config {
         pub fn password(&self) -> &str {
             &self.password
         }
+
+        pub fn flood_exempt(&self) -> bool {
+            self.flood_exempt.unwrap_or(true)
+        }
+
+        pub fn commands_per_second(&self) -> f64 {
+            self.commands_per_second.unwrap_or(2.0)
+        }
     }
 
     #[derive(Clone, Debug, Deserialize)]
@@ -90,6 +115,8 @@ pub mod config {
         ignore_user: Option<Vec<String>>,
         #[serde(alias = "whitelist-ip")]
         whitelist_ip: Option<Vec<String>>,
+        #[serde(alias = "bot-server-group")]
+        bot_server_group: Option<i64>,
         #[cfg(feature = "tracker")]
         #[serde(alias = "track-channel-member")]
         track_channel_member: Option<String>,
@@ -124,6 +151,10 @@ pub mod config {
             self.whitelist_ip.clone().unwrap_or_default()
         }
 
+        pub fn bot_server_group(&self) -> Option<i64> {
+            self.bot_server_group
+        }
+
         #[cfg(feature = "tracker")]
         pub fn track_channel_member(&self) -> &Option<String> {
             &self.track_channel_member
@@ -134,6 +165,8 @@ pub mod config {
     pub struct Message {
         #[serde(alias = "move-to-channel")]
         move_to_channel: Option<String>,
+        #[serde(alias = "channel-full")]
+        channel_full: Option<String>,
     }
 
     impl Message {
@@ -142,6 +175,12 @@ pub mod config {
                 .clone()
                 .unwrap_or_else(|| "You have been moved into your channel.".into())
         }
+
+        pub fn channel_full(&self) -> String {
+            self.channel_full.clone().unwrap_or_else(|| {
+                "Your channel is full, you will be moved once there is room.".into()
+            })
+        }
     }
 
     #[derive(Clone, Debug, Deserialize)]
@@ -222,12 +261,44 @@ pub mod config {
         }
     }
 
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct AutoChannel {
+        #[serde(alias = "announce-in-channel")]
+        announce_in_channel: Option<i64>,
+        #[serde(alias = "move-cooldown", default)]
+        move_cooldown: u64,
+        #[serde(alias = "transfer-ownership-on-leave", default)]
+        transfer_ownership_on_leave: bool,
+        #[serde(alias = "max-name-attempts")]
+        max_name_attempts: Option<usize>,
+    }
+
+    impl AutoChannel {
+        pub fn announce_in_channel(&self) -> Option<i64> {
+            self.announce_in_channel
+        }
+
+        pub fn move_cooldown(&self) -> Duration {
+            Duration::from_secs(self.move_cooldown)
+        }
+
+        pub fn transfer_ownership_on_leave(&self) -> bool {
+            self.transfer_ownership_on_leave
+        }
+
+        pub fn max_name_attempts(&self) -> usize {
+            self.max_name_attempts.unwrap_or(10)
+        }
+    }
+
     #[derive(Clone, Debug, Deserialize)]
     pub struct Config {
         server: Server,
         misc: Misc,
         #[serde(default, alias = "mute-porter")]
         mute_porter: MutePorter,
+        #[serde(default, alias = "auto-channel")]
+        auto_channel: AutoChannel,
         #[serde(alias = "custom-message")]
         custom_message: Option<Message>,
         permissions: Option<Vec<Permission>>,
@@ -301,6 +372,10 @@ pub mod config {
             &self.mute_porter
         }
 
+        pub fn auto_channel(&self) -> &AutoChannel {
+            &self.auto_channel
+        }
+
         pub fn additional(&self) -> &[String] {
             &self.additional
         }
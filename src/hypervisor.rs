@@ -2,7 +2,9 @@ mod inner {
     use super::{
         ClientResult, SYSTEMD_MODE, SYSTEMD_MODE_RETRIES_TIMES, types::SubThreadExitReason,
     };
-    use crate::auto_channel::{AutoChannelInstance, auto_channel_staff};
+    use crate::auto_channel::{
+        AutoChannelInstance, StatRequestHelper, Stats, auto_channel_staff, serve_stats,
+    };
     use crate::configure::Config;
     use crate::configure::config::RawQuery;
     use crate::observer::{PrivateMessageRequest, observer_thread};
@@ -10,6 +12,7 @@ mod inner {
     #[cfg(feature = "tracker")]
     use crate::plugins::tracker::DatabaseHelper;
     use crate::socketlib::SocketConn;
+    use crate::spawn_named;
     use crate::telegram::BindTelegramHelper;
     #[cfg(feature = "tracker")]
     use crate::types::EventHelperTrait;
@@ -22,7 +25,7 @@ mod inner {
     use std::time::Duration;
     #[cfg(feature = "tracker")]
     use tap::TapOptional;
-    use tokio::sync::{Notify, mpsc};
+    use tokio::sync::{Notify, mpsc, watch};
     use tuple_conv::RepeatedTuple;
 
     async fn try_init_connection(
@@ -64,7 +67,26 @@ mod inner {
     }
 
     async fn init_connection(cfg: &RawQuery, sid: i64) -> anyhow::Result<SocketConn> {
-        let mut conn = SocketConn::connect(&cfg.server(), cfg.port()).await?;
+        #[cfg(feature = "ssh")]
+        if cfg.ssh() {
+            // Authenticated by SSH, no `login` needed
+            let mut conn =
+                SocketConn::connect_ssh(&cfg.server(), cfg.port(), cfg.user(), cfg.password())
+                    .await?;
+            conn.set_rate_limit(cfg.commands_per_second(), cfg.flood_exempt());
+            conn.select_server(sid)
+                .await
+                .map_err(|e| anyhow!("Select server id failed: {e:?}"))?;
+            return Ok(conn);
+        }
+
+        let mut conn = SocketConn::connect_with_rate_limit(
+            &cfg.server(),
+            cfg.port(),
+            cfg.commands_per_second(),
+            cfg.flood_exempt(),
+        )
+        .await?;
         conn.login(cfg.user(), cfg.password())
             .await
             .map_err(|e| anyhow!("Login failed. {e:?}"))?;
@@ -103,6 +125,13 @@ mod inner {
         #[cfg(not(feature = "tracker"))]
         let (user_tracker, tracker_controller) = PseudoEventHelper::new();
 
+        let (stats_sender, stats_receiver) = watch::channel(Stats::default());
+        let (stat_helper, stat_request_receiver) = StatRequestHelper::new(16);
+        spawn_named(
+            &format!("autochannel-stats-{thread_id}"),
+            serve_stats(stat_request_receiver, stats_receiver),
+        );
+
         let auto_channel_future = auto_channel_staff(
             auto_channel_connection,
             trigger_receiver,
@@ -111,31 +140,36 @@ mod inner {
             thread_id.clone(),
             kv_map,
             user_map,
+            stats_sender,
         );
 
-        let auto_channel_handler = tokio::spawn(async move {
-            auto_channel_future
-                .await
-                .inspect_err(|e| log::error!("Early error detected: {e:?}"))
-        });
+        let auto_channel_handler =
+            spawn_named(&format!("autochannel-worker-{thread_id}"), async move {
+                auto_channel_future
+                    .await
+                    .inspect_err(|e| log::error!("Early error detected: {e:?}"))
+            });
 
         let auto_channel_instance =
             AutoChannelInstance::new(config.server().channels(), Some(trigger_sender));
 
-        let observer_handler = tokio::spawn(observer_thread(
-            observer_connection,
-            private_message_receiver,
-            telegram_sender,
-            auto_channel_instance,
-            config.clone(),
-            Box::new(tracker_controller.clone()),
-            thread_id.clone(),
-        ));
+        let observer_handler = spawn_named(
+            &format!("observer-{thread_id}"),
+            observer_thread(
+                observer_connection,
+                private_message_receiver,
+                telegram_sender,
+                auto_channel_instance,
+                config.clone(),
+                Box::new(tracker_controller.clone()),
+                thread_id.clone(),
+            ),
+        );
 
         tokio::select! {
             ret = async {
                 notifier.notified().await;
-                info!("[{thread_id}] Recv SIGINT, send signal to thread.",);
+                info!("[{thread_id}] Recv exit signal, send signal to thread.",);
                 private_message_sender
                     .send(PrivateMessageRequest::Terminate)
                     .await
@@ -177,6 +211,10 @@ mod inner {
             ret?;
         }
 
+        if let Some(stats) = stat_helper.query().await {
+            info!("[{thread_id}] Auto channel stats: {stats:?}");
+        }
+
         Ok(())
     }
 
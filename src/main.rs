@@ -21,6 +21,40 @@ const DEFAULT_LEVEL_DB_LOCATION: &str = "./level.db";
 pub static OBSERVER_NICKNAME_OVERRIDE: OnceLock<String> = OnceLock::new();
 pub static AUTO_CHANNEL_NICKNAME_OVERRIDE: OnceLock<String> = OnceLock::new();
 
+/// Spawn `future` as a task named `name`, the name is visible in tokio-console when built with
+/// `tokio-console` feature and `--cfg tokio_unstable`.
+pub fn spawn_named<F>(name: &str, future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+    return tokio::task::Builder::new()
+        .name(name)
+        .spawn(future)
+        .expect("Unable spawn task");
+    #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
+
+/// Wait for SIGINT, or SIGTERM (sent by systemd or docker stop) on unix
+async fn wait_exit_signal() {
+    #[cfg(unix)]
+    {
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).unwrap();
+        tokio::select! {
+            ret = tokio::signal::ctrl_c() => ret.unwrap(),
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.unwrap();
+}
+
 async fn start_services(config: String, systemd_mode: bool) -> anyhow::Result<()> {
     let notify = Arc::new(Notify::new());
     let exit_notify = Arc::new(Notify::new());
@@ -32,13 +66,13 @@ async fn start_services(config: String, systemd_mode: bool) -> anyhow::Result<()
 
     tokio::select! {
         _ = async {
-            tokio::signal::ctrl_c().await.unwrap();
-            // First ctrl_c signal
+            wait_exit_signal().await;
+            // First exit signal
             notify.notify_waiters();
-            tokio::signal::ctrl_c().await.unwrap();
+            wait_exit_signal().await;
             // Notify again
             notify.notify_waiters();
-            tokio::signal::ctrl_c().await.unwrap();
+            wait_exit_signal().await;
             error!("Force exit!");
             std::process::exit(137);
         } => {
@@ -128,6 +162,10 @@ fn main() -> anyhow::Result<()> {
 
     let configure = matches.get_one::<String>("CONFIG_FILE").unwrap();
 
+    // Listen on 127.0.0.1:6669 for tokio-console, needs `--cfg tokio_unstable` to show tasks
+    #[cfg(feature = "tokio-console")]
+    console_subscriber::init();
+
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
@@ -1,4 +1,4 @@
-use crate::auto_channel::AutoChannelInstance;
+use crate::auto_channel::{AutoChannelInstance, RECONNECT_INTERVAL};
 use crate::configure::Config;
 use crate::socketlib::SocketConn;
 use crate::types::EventHelperTrait;
@@ -78,8 +78,8 @@ mod processor {
     use super::Arguments;
     use crate::socketlib::SocketConn;
     use crate::types::{
-        BanEntry, FromQueryString, NotifyClientEnterView, NotifyClientLeftView,
-        NotifyClientMovedView, NotifyTextMessage,
+        BanEntry, ChannelDeletedEvent, FromQueryString, NotifyClientEnterView,
+        NotifyClientLeftView, NotifyClientMovedView, NotifyTextMessage,
     };
     use anyhow::anyhow;
     use futures_util::FutureExt;
@@ -244,6 +244,20 @@ mod processor {
             Ok(())
         }
 
+        pub(super) async fn channel_deleted(line: &str, argument: &Arguments<'_>) -> Result {
+            let view = ChannelDeletedEvent::from_query(line)
+                .map_err(|e| anyhow!("Got error while deserialize channel deleted view: {e:?}"))?;
+            argument
+                .monitor_channel()
+                .send_channel_deleted(view.cid())
+                .await
+                .map(|sent| {
+                    if sent {
+                        trace!("[{}] Notify auto channel thread", argument.thread_id())
+                    }
+                })
+        }
+
         pub(super) async fn ban_list(
             line: &str,
             argument: &Arguments<'_>,
@@ -255,7 +269,8 @@ mod processor {
             for entry in line.split('|').map(BanEntry::from_query) {
                 let entry = entry?;
                 if argument.whitelist_ip().iter().any(|ip| entry.ip().eq(ip)) {
-                    conn.ban_del(entry.ban_id()).await.map(|_| {
+                    // Status line is handled by read loop
+                    conn.ban_del_unchecked(entry.ban_id()).await.map(|_| {
                         info!(
                             "[{}] Remove whitelist ip {} from ban list (was {entry})",
                             argument.thread_id(),
@@ -292,12 +307,19 @@ async fn staff(
     if line.contains("notifytextmessage") && argument.monitor_channel().valid() {
         return Processor::user_text(line, argument).await;
     }
+    if line.starts_with("notifychanneldeleted") && argument.monitor_channel().valid() {
+        return Processor::channel_deleted(line, argument).await;
+    }
     if line.starts_with("banid") {
         return Processor::ban_list(line, argument, conn).await;
     }
     if line.contains("virtualserver_status=") {
         return Ok(());
     }
+    // Status of commands sent without reading response
+    if line.starts_with("error id=") && !line.starts_with("error id=0 ") {
+        warn!("[{}] Command failed: {line}", argument.thread_id());
+    }
     Ok(())
 }
 
@@ -325,6 +347,21 @@ pub async fn observer_thread(
     .await
     .map_err(|e| anyhow!("Got error while change nickname: {e:?}"))?;
 
+    match conn.query_version().await {
+        Ok(version) => info!("[{thread_id}] Server version: {version}"),
+        Err(e) => warn!("[{thread_id}] Unable query server version: {e:?}"),
+    }
+
+    let bot_server_group = config.server().bot_server_group();
+    if let Some(sgid) = bot_server_group {
+        // 2561: already member of group
+        if let Err(e) = conn.add_self_to_server_group(sgid).await
+            && e.code() != 2561
+        {
+            return Err(anyhow!("Unable add self to server group {sgid}: {e:?}"));
+        }
+    }
+
     let mut client_map: HashMap<i64, (String, bool)> = HashMap::new();
 
     for client in conn
@@ -367,6 +404,22 @@ pub async fn observer_thread(
     }
 
     loop {
+        if conn.connection_lost() {
+            warn!("[{thread_id}] Connection lost, try to reconnect");
+            // Notify registrations are replayed by reconnect
+            if let Err(e) = conn.reconnect().await {
+                error!("[{thread_id}] Reconnect failed: {e:?}");
+                match tokio::time::timeout(RECONNECT_INTERVAL, recv.recv()).await {
+                    Ok(Some(PrivateMessageRequest::Terminate) | None) => break,
+                    _ => continue,
+                }
+            }
+            info!("[{thread_id}] Reconnected");
+            if !whitelist_ip.is_empty() {
+                conn.write_data("banlist\n\r").await.ok();
+            }
+        }
+
         tokio::select! {
             message = tokio::time::timeout(Duration::from_millis(interval), recv.recv()) => {
                 let message = match message {
@@ -376,22 +429,45 @@ pub async fn observer_thread(
                 match message {
                     PrivateMessageRequest::Message(client_id, message) => {
 
-                        conn.send_text_message_unchecked(client_id, &message)
-                        .await
-                        .map(|_| trace!("[{thread_id}] Send message to {client_id}"))
-                        .map_err(|e| {
-                            anyhow!("[{thread_id}] Got error while send message to {client_id} {e:?}")
-                        })?;
+                        match conn.send_text_message_unchecked(client_id, &message).await {
+                            Ok(_) => trace!("[{thread_id}] Send message to {client_id}"),
+                            Err(e) if conn.connection_lost() => {
+                                warn!("[{thread_id}] Unable send message to {client_id}: {e:?}")
+                            }
+                            Err(e) => {
+                                return Err(anyhow!(
+                                    "[{thread_id}] Got error while send message to {client_id} {e:?}"
+                                ))
+                            }
+                        }
                         continue
                     }
                     PrivateMessageRequest::KeepAlive => {
-                        conn.send_keepalive().await
-                            .map_err(|e| {
-                                anyhow!("Got error while write data in keep alive function: {e:?}")
-                            })?;
+                        // Every command on this connection is sent without reading response,
+                        // responses are handled as ordinary lines by `read_lines` below
+                        if let Err(e) = conn.send_keepalive_unchecked().await {
+                            if conn.connection_lost() {
+                                continue;
+                            }
+                            return Err(anyhow!(
+                                "Got error while write data in keep alive function: {e:?}"
+                            ));
+                        }
+                        // Keep removing whitelisted IPs from ban list
+                        if !whitelist_ip.is_empty() {
+                            conn.write_data("banlist\n\r").await.ok();
+                        }
                     }
                     PrivateMessageRequest::Terminate => {
                         info!("[{thread_id}] Exit from staff thread!");
+                        if let Some(sgid) = bot_server_group {
+                            conn.remove_self_from_server_group(sgid)
+                                .await
+                                .inspect_err(|e| {
+                                    warn!("[{thread_id}] Unable remove self from server group {sgid}: {e:?}")
+                                })
+                                .ok();
+                        }
                         conn.logout().await.ok();
                         break;
                     }
@@ -404,10 +480,15 @@ pub async fn observer_thread(
             }
         }
 
-        let data = conn
-            .read_data()
-            .await
-            .map_err(|e| anyhow!("Got error while read data: {e:?}"))?;
+        let data = match conn.read_lines().await {
+            Ok(data) => data,
+            // Reconnect at next iteration
+            Err(e) if conn.connection_lost() => {
+                warn!("[{thread_id}] Got error while read data: {e:?}");
+                continue;
+            }
+            Err(e) => return Err(anyhow!("Got error while read data: {e:?}")),
+        };
 
         if !matches!(&data, Some(x) if !x.is_empty()) {
             continue;
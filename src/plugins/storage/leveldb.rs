@@ -3,7 +3,9 @@ use tokio::sync::mpsc::Receiver;
 //pub type OnceSender<T> = tokio::sync::oneshot::Sender<T>;
 pub use rusty_leveldb::Result;
 
-use super::{ForkConnection, KVMap};
+use rusty_leveldb::LdbIterator;
+
+use super::{ForkConnection, KVMap, glob_match};
 
 #[derive(Clone, Debug)]
 pub struct ConnAgent(DatabaseHelper);
@@ -37,6 +39,8 @@ kstool_helper_generator::oneshot_helper! {
         ),
         #[ret(Result<()>)]
         Delete(String),
+        #[ret(Result<Vec<String>>)]
+        ListKeys(String),
         Exit,
     }
 }
@@ -94,6 +98,21 @@ impl LevelDB {
                     sender.send(db.delete(k.as_bytes())).ok();
                     db.flush()?;
                 }
+                DatabaseEvent::ListKeys(pattern, sender) => {
+                    sender
+                        .send(db.new_iter().map(|mut iter| {
+                            let mut keys = Vec::new();
+                            while let Some((k, _)) = iter.next() {
+                                if let Ok(k) = String::from_utf8(k)
+                                    && glob_match(&pattern, &k)
+                                {
+                                    keys.push(k);
+                                }
+                            }
+                            keys
+                        }))
+                        .ok();
+                }
                 DatabaseEvent::Exit => break,
             }
         }
@@ -130,6 +149,13 @@ impl KVMap for ConnAgent {
             .await
             .map_or(Ok(None), |v| v.map_err(anyhow::Error::from))
     }
+
+    async fn list_keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>> {
+        self.0
+            .list_keys(pattern.to_string())
+            .await
+            .map_or(Ok(Vec::new()), |v| v.map_err(anyhow::Error::from))
+    }
 }
 
 #[cfg(test)]
@@ -146,6 +172,8 @@ mod test {
             Some("value".to_string())
         );
         conn.set("key".to_string(), "value".to_string()).await?;
+        conn.set("other".to_string(), "value".to_string()).await?;
+        assert_eq!(conn.list_keys("k*").await?, vec!["key".to_string()]);
         conn.delete("key".to_string()).await?;
         assert_eq!(conn.get("key".to_string()).await?, None);
 
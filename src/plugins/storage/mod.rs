@@ -16,6 +16,33 @@ pub trait KVMap: Send + Sync {
     async fn delete(&mut self, key: String) -> anyhow::Result<()>;
 
     async fn get(&mut self, key: String) -> anyhow::Result<Option<String>>;
+
+    /// List keys matching glob style `pattern` (`*` and `?` wildcards, as Redis `SCAN MATCH`).
+    async fn list_keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>>;
+}
+
+/// Match `key` against glob style `pattern`, only `*` and `?` are special.
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let (pattern, key) = (pattern.as_bytes(), key.as_bytes());
+    let (mut p, mut k) = (0, 0);
+    // Position of last `*` in pattern and the key position it matched up to
+    let mut star = None;
+    while k < key.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == key[k]) {
+            p += 1;
+            k += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, k));
+            p += 1;
+        } else if let Some((star_p, star_k)) = star {
+            p = star_p + 1;
+            k = star_k + 1;
+            star = Some((star_p, star_k + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
 }
 
 #[async_trait::async_trait]
@@ -71,3 +98,24 @@ impl From<leveldb::LevelDB> for Backend {
         Self::LevelDB(value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::glob_match;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("ts_autochannel_*", "ts_autochannel_1_abc_2"));
+        assert!(glob_match(
+            "ts_autochannel_*_abc_*",
+            "ts_autochannel_1_abc_2"
+        ));
+        assert!(glob_match("key?", "key1"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match(
+            "ts_autochannel_*_def_*",
+            "ts_autochannel_1_abc_2"
+        ));
+        assert!(!glob_match("key?", "key"));
+    }
+}
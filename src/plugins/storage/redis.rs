@@ -48,4 +48,13 @@ impl KVMap for RedisAgent {
             .get::<_, Option<String>>(key.to_string())
             .await?)
     }
+
+    async fn list_keys(&mut self, pattern: &str) -> anyhow::Result<Vec<String>> {
+        let mut iter = self.conn.scan_match::<_, String>(pattern).await?;
+        let mut keys = Vec::new();
+        while let Some(key) = iter.next_item().await {
+            keys.push(key);
+        }
+        Ok(keys)
+    }
 }
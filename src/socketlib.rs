@@ -1,18 +1,422 @@
+use crate::plugins::KVMap;
 use crate::types::{
-    Channel, Client, ClientInfo, CreateChannel, DatabaseId, QueryError, QueryResult, ServerInfo,
-    WhoAmI,
+    BanEntry, Channel, ChannelGroupMember, ChannelInfo, ChannelMatch, ChannelName,
+    ChannelWithDescription, ChannelWithFlags, ChannelWithLimits, ChannelWithTopic,
+    ChannelWithVoice, Client, ClientBandwidth, ClientDatabaseInfo, ClientDbEntry,
+    ClientGroupMembership, ClientInfo, ClientName, ClientUid, ClientWithBadges, ClientWithIp,
+    ConnectionStats, CreateChannel, CreatedBan, CreatedChannelGroup, DatabaseId, DefaultGroups,
+    DynamicClient, FileInfo, HostInfo, HostMessageMode, InstanceSettings, LogLevel,
+    NamedPermission, Permission, PermissionDefinition, QueryError, QueryResult, QuotaInfo,
+    SelfInfo, ServerGroup, ServerGroupClient, ServerGroupId, ServerGroupMember, ServerGroupType,
+    ServerInfo, TransferQuotas, VersionInfo, VirtualServerWithStats, WhoAmI,
 };
-use crate::types::{FromQueryString, QueryStatus};
+use crate::types::{FromQueryString, QueryStatus, escape};
 use anyhow::anyhow;
-use log::{error, warn};
+use log::{error, info, warn};
+use rate_limit::RateLimiter;
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, Interest};
 use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio::time::Instant;
+use transport::Transport;
 
 const BUFFER_SIZE: usize = 512;
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(2);
+const PIPELINE_BATCH_SIZE: usize = 10;
+/// Minimum interval between server wide messages, to stay away from anti-flood
+const SERVER_MESSAGE_INTERVAL: Duration = Duration::from_secs(5);
+
+mod rate_limit {
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    /// Token bucket refilled at `per_second`, holds at most one second of commands, so any
+    /// 3 seconds window (server's default flood time) sees at most `4 * per_second` commands.
+    pub(super) struct RateLimiter {
+        per_second: f64,
+        tokens: f64,
+        last_refill: Instant,
+    }
+
+    impl RateLimiter {
+        pub(super) fn new(per_second: f64) -> Self {
+            let per_second = per_second.max(0.1);
+            Self {
+                per_second,
+                tokens: per_second.max(1.0),
+                last_refill: Instant::now(),
+            }
+        }
+
+        /// Take a token, sleep if bucket is empty.
+        pub(super) async fn acquire(&mut self) {
+            let now = Instant::now();
+            self.tokens = (self.tokens + (now - self.last_refill).as_secs_f64() * self.per_second)
+                .min(self.per_second.max(1.0));
+            self.last_refill = now;
+            self.tokens -= 1.0;
+            if self.tokens < 0.0 {
+                // Debt is repaid by next refill
+                tokio::time::sleep(Duration::from_secs_f64(-self.tokens / self.per_second)).await;
+            }
+        }
+    }
+}
+
+mod transport {
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, AsyncWrite, Interest, ReadBuf, Ready};
+    use tokio::net::TcpStream;
+    #[cfg(unix)]
+    use tokio::net::UnixStream;
+
+    #[cfg(feature = "ssh")]
+    use super::ssh::SshStream;
+
+    pub(super) enum Transport {
+        Tcp(TcpStream),
+        #[cfg(unix)]
+        Unix(UnixStream),
+        #[cfg(feature = "ssh")]
+        Ssh(SshStream),
+    }
+
+    impl Transport {
+        pub(super) async fn ready(&mut self, interest: Interest) -> io::Result<Ready> {
+            match self {
+                Self::Tcp(stream) => stream.ready(interest).await,
+                #[cfg(unix)]
+                Self::Unix(stream) => stream.ready(interest).await,
+                #[cfg(feature = "ssh")]
+                Self::Ssh(stream) => {
+                    debug_assert!(interest.is_readable());
+                    stream.readable().await;
+                    Ok(Ready::READABLE)
+                }
+            }
+        }
+    }
+
+    impl AsyncRead for Transport {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                Self::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+                #[cfg(unix)]
+                Self::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+                #[cfg(feature = "ssh")]
+                Self::Ssh(stream) => Pin::new(stream).poll_read(cx, buf),
+            }
+        }
+    }
+
+    impl AsyncWrite for Transport {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            match self.get_mut() {
+                Self::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+                #[cfg(unix)]
+                Self::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+                #[cfg(feature = "ssh")]
+                Self::Ssh(stream) => Pin::new(stream).poll_write(cx, buf),
+            }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                Self::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+                #[cfg(unix)]
+                Self::Unix(stream) => Pin::new(stream).poll_flush(cx),
+                #[cfg(feature = "ssh")]
+                Self::Ssh(stream) => Pin::new(stream).poll_flush(cx),
+            }
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            match self.get_mut() {
+                Self::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+                #[cfg(unix)]
+                Self::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+                #[cfg(feature = "ssh")]
+                Self::Ssh(stream) => Pin::new(stream).poll_shutdown(cx),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ssh")]
+mod ssh {
+    use anyhow::anyhow;
+    use ssh2::{Channel, Session};
+    use std::io::{self, ErrorKind, Read, Write};
+    use std::pin::Pin;
+    use std::sync::mpsc as std_mpsc;
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio::sync::mpsc;
+
+    /// Sleep of pump thread when there is nothing to read or write
+    const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+    /// libssh2 is blocking, so the channel is owned by a thread which pumps data between it and
+    /// this stream.
+    pub(in crate::socketlib) struct SshStream {
+        incoming: mpsc::UnboundedReceiver<Vec<u8>>,
+        // Received but not read yet
+        pending: Vec<u8>,
+        outgoing: std_mpsc::Sender<Vec<u8>>,
+    }
+
+    impl SshStream {
+        pub(in crate::socketlib) async fn connect(
+            server: &str,
+            port: u16,
+            user: &str,
+            password: &str,
+        ) -> anyhow::Result<Self> {
+            let (server, user, password) =
+                (server.to_string(), user.to_string(), password.to_string());
+            let (session, channel) = tokio::task::spawn_blocking(move || {
+                let tcp = std::net::TcpStream::connect((server.as_str(), port))
+                    .map_err(|e| anyhow!("Got error while connect to {server}:{port} {e:?}"))?;
+                let mut session = Session::new()?;
+                session.set_tcp_stream(tcp);
+                session
+                    .handshake()
+                    .map_err(|e| anyhow!("SSH handshake failed: {e:?}"))?;
+                session
+                    .userauth_password(&user, &password)
+                    .map_err(|e| anyhow!("SSH authentication failed: {e:?}"))?;
+                let mut channel = session.channel_session()?;
+                channel.shell()?;
+                session.set_blocking(false);
+                anyhow::Ok((session, channel))
+            })
+            .await??;
+
+            let (incoming_sender, incoming) = mpsc::unbounded_channel();
+            let (outgoing, outgoing_receiver) = std_mpsc::channel();
+            std::thread::Builder::new()
+                .name("ssh-query".into())
+                .spawn(move || Self::pump(session, channel, incoming_sender, outgoing_receiver))?;
+            Ok(Self {
+                incoming,
+                pending: Vec::new(),
+                outgoing,
+            })
+        }
+
+        /// Wait until there is data to read, or channel is closed.
+        pub(in crate::socketlib) async fn readable(&mut self) {
+            if self.pending.is_empty()
+                && let Some(data) = self.incoming.recv().await
+            {
+                self.pending = data;
+            }
+        }
+
+        fn pump(
+            session: Session,
+            mut channel: Channel,
+            incoming: mpsc::UnboundedSender<Vec<u8>>,
+            outgoing: std_mpsc::Receiver<Vec<u8>>,
+        ) {
+            let mut buffer = [0u8; 4096];
+            loop {
+                let mut idle = true;
+                match outgoing.try_recv() {
+                    Ok(data) => {
+                        idle = false;
+                        if Self::write_all(&mut channel, &data).is_err() {
+                            break;
+                        }
+                    }
+                    Err(std_mpsc::TryRecvError::Empty) => {}
+                    // Stream dropped
+                    Err(std_mpsc::TryRecvError::Disconnected) => break,
+                }
+                match channel.read(&mut buffer) {
+                    Ok(0) if channel.eof() => break,
+                    Ok(0) => {}
+                    Ok(size) => {
+                        idle = false;
+                        if incoming.send(buffer[..size].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                    Err(_) => break,
+                }
+                if idle {
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            }
+            session.set_blocking(true);
+            channel.close().ok();
+        }
+
+        fn write_all(channel: &mut Channel, mut data: &[u8]) -> io::Result<()> {
+            while !data.is_empty() {
+                match channel.write(data) {
+                    Ok(size) => data = &data[size..],
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            loop {
+                match channel.flush() {
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL)
+                    }
+                    ret => return ret,
+                }
+            }
+        }
+    }
+
+    impl AsyncRead for SshStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let this = self.get_mut();
+            if this.pending.is_empty() {
+                match this.incoming.poll_recv(cx) {
+                    Poll::Ready(Some(data)) => this.pending = data,
+                    // Pump thread exited, read nothing means EOF
+                    Poll::Ready(None) => return Poll::Ready(Ok(())),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            let size = this.pending.len().min(buf.remaining());
+            buf.put_slice(&this.pending[..size]);
+            this.pending.drain(..size);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for SshStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(
+                self.outgoing
+                    .send(buf.to_vec())
+                    .map(|_| buf.len())
+                    .map_err(|_| io::Error::from(ErrorKind::BrokenPipe)),
+            )
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// Where the connection goes, kept for [`SocketConn::reconnect`].
+#[derive(Clone, Debug)]
+enum Endpoint {
+    Tcp(String, u16),
+    #[cfg(unix)]
+    Unix(PathBuf),
+    #[cfg(feature = "ssh")]
+    Ssh {
+        server: String,
+        port: u16,
+        user: String,
+        password: String,
+    },
+}
+
+impl Endpoint {
+    async fn open(&self) -> anyhow::Result<Transport> {
+        match self {
+            Self::Tcp(server, port) => TcpStream::connect(format!("{server}:{port}"))
+                .await
+                .map(Transport::Tcp)
+                .map_err(|e| anyhow!("Got error while connect to {server}:{port} {e:?}")),
+            #[cfg(unix)]
+            Self::Unix(path) => UnixStream::connect(path)
+                .await
+                .map(Transport::Unix)
+                .map_err(|e| anyhow!("Got error while connect to {} {e:?}", path.display())),
+            #[cfg(feature = "ssh")]
+            Self::Ssh {
+                server,
+                port,
+                user,
+                password,
+            } => ssh::SshStream::connect(server, *port, user, password)
+                .await
+                .map(Transport::Ssh),
+        }
+    }
+
+    /// Welcome message is `TS3` line and a `Welcome to ...` line, without status line. SSH
+    /// has its own identification, so only the `Welcome to ...` line is sent.
+    fn welcome_complete(&self, data: &[u8]) -> bool {
+        let lines = data.windows(2).filter(|w| w == b"\n\r").count();
+        match self {
+            #[cfg(feature = "ssh")]
+            Self::Ssh { .. } => lines >= 1 && data.windows(7).any(|w| w == b"Welcome"),
+            _ => lines >= 2,
+        }
+    }
+}
+
+/// State replayed by [`SocketConn::reconnect`], recorded only after server accepted it.
+#[derive(Clone, Debug, Default)]
+struct Session {
+    credentials: Option<(String, String)>,
+    server_id: Option<i64>,
+    nickname: Option<String>,
+    // Channel id and password of the last `set_own_channel`
+    channel: Option<(i64, Option<String>)>,
+    // Successful `servernotifyregister` payloads
+    notify_payloads: Vec<String>,
+}
 
 pub struct SocketConn {
-    conn: TcpStream,
+    conn: Transport,
+    endpoint: Endpoint,
+    session: Session,
+    connection_lost: bool,
+    client_id: Option<i64>,
+    client_database_id: Option<i64>,
+    verbose: bool,
+    // Client unique id => client database id, never changes while server is alive
+    uid_cache: HashMap<String, i64>,
+    // Client unique id => last known nickname
+    display_name_cache: HashMap<String, String>,
+    // Permission id => permission name, loaded on first use
+    permission_names: HashMap<u64, String>,
+    last_server_message: Option<Instant>,
+    read_timeout: Duration,
+    // `None` if login is exempt from flood control
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl SocketConn {
@@ -53,31 +457,77 @@ impl SocketConn {
         Ok(None)
     }
 
+    /// Read until a complete response (ends with its `error id=` status line) is received.
     pub(crate) async fn read_data(&mut self) -> anyhow::Result<Option<String>> {
+        self.read_until(|data| {
+            data.ends_with(b"\n\r") && data.windows(9).any(|w| w == b"error id=")
+        })
+        .await
+    }
+
+    /// Read until complete lines are received, notifies have no status line.
+    pub(crate) async fn read_lines(&mut self) -> anyhow::Result<Option<String>> {
+        self.read_until(|data| data.ends_with(b"\n\r")).await
+    }
+
+    /// Short read doesn't mean end of message, so only `complete` (or EOF) ends reading, with
+    /// timeout as safety net. Return `None` if nothing received before timeout.
+    async fn read_until(
+        &mut self,
+        complete: impl Fn(&[u8]) -> bool,
+    ) -> anyhow::Result<Option<String>> {
         let mut buffer = [0u8; BUFFER_SIZE];
-        let mut ret = String::new();
+        // Decode after whole response received, multibyte character may be split across reads
+        let mut ret = Vec::new();
         loop {
             let size = if let Ok(data) =
-                tokio::time::timeout(Duration::from_secs(2), self.conn.read(&mut buffer)).await
+                tokio::time::timeout(self.read_timeout, self.conn.read(&mut buffer)).await
             {
                 match data {
+                    Ok(0) if ret.is_empty() => {
+                        self.connection_lost = true;
+                        return Err(anyhow!("Connection closed by server"));
+                    }
                     Ok(size) => size,
-                    Err(e) => return Err(anyhow!("Got error while read data: {e:?}")),
+                    Err(e) => {
+                        self.connection_lost = true;
+                        return Err(anyhow!("Got error while read data: {e:?}"));
+                    }
                 }
-            } else {
+            } else if ret.is_empty() {
                 return Ok(None);
+            } else {
+                warn!("Read timeout with incomplete data: {} bytes", ret.len());
+                break;
             };
 
-            ret.push_str(&String::from_utf8_lossy(&buffer[..size]));
-            if size < BUFFER_SIZE || (ret.contains("error id=") && ret.ends_with("\n\r")) {
+            ret.extend_from_slice(&buffer[..size]);
+            if size == 0 || complete(&ret) {
                 break;
             }
         }
-        Ok(Some(ret))
+        Ok(Some(String::from_utf8_lossy(&ret).into_owned()))
     }
 
     pub(crate) async fn write_data(&mut self, payload: &str) -> anyhow::Result<()> {
         debug_assert!(payload.ends_with("\n\r"));
+        if self.rate_limiter.is_none() {
+            return self.write_raw(payload).await;
+        }
+        // Pipelined payload contains several commands, each of them counts
+        for command in payload.split_inclusive("\n\r") {
+            if let Some(rate_limiter) = self.rate_limiter.as_mut() {
+                rate_limiter.acquire().await;
+            }
+            self.write_raw(command).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_raw(&mut self, payload: &str) -> anyhow::Result<()> {
+        if self.verbose {
+            info!("Send: {payload:?}");
+        }
         self.conn
             .write(payload.as_bytes())
             .await
@@ -89,7 +539,10 @@ impl SocketConn {
                     )
                 }
             })
-            .map_err(|e| anyhow!("Got error while send data: {e:?}"))?;
+            .map_err(|e| {
+                self.connection_lost = true;
+                anyhow!("Got error while send data: {e:?}")
+            })?;
         /*self.conn
         .flush()
         .await
@@ -104,6 +557,34 @@ impl SocketConn {
             .ok_or_else(|| anyhow!("Return data is None"))
     }
 
+    /// Find end of first complete response (including its status line) in `content`.
+    fn first_response_end(content: &str) -> Option<usize> {
+        let start = if content.starts_with("error id=") {
+            0
+        } else {
+            content.find("\rerror id=")? + 1
+        };
+        content[start..].find("\n\r").map(|end| start + end + 2)
+    }
+
+    /// Write all payloads in one call, then read until every payload get its response.
+    async fn pipeline_operation(&mut self, payloads: &[String]) -> anyhow::Result<Vec<String>> {
+        self.write_data(&payloads.concat()).await?;
+        let mut responses = Vec::with_capacity(payloads.len());
+        let mut pending = String::new();
+        while responses.len() < payloads.len() {
+            let data = self
+                .read_data()
+                .await?
+                .ok_or_else(|| anyhow!("Return data is None"))?;
+            pending.push_str(&data);
+            while let Some(end) = Self::first_response_end(&pending) {
+                responses.push(pending.drain(..end).collect());
+            }
+        }
+        Ok(responses)
+    }
+
     async fn basic_operation(&mut self, payload: &str) -> QueryResult<()> {
         let data = self.write_and_read(payload).await?;
         Self::decode_status(data)?;
@@ -139,50 +620,332 @@ impl SocketConn {
             .map(|r| r.map(|mut v| v.swap_remove(0)))
     }
 
+    /// Server return error id 1281 (database empty result set) instead of empty list.
+    async fn query_operation_or_empty<T: FromQueryString + Sized>(
+        &mut self,
+        payload: &str,
+    ) -> QueryResult<Vec<T>> {
+        match self.query_operation(payload).await {
+            Ok(ret) => Ok(ret.unwrap_or_default()),
+            Err(e) if e.code() == 1281 => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
     fn escape(s: &str) -> String {
-        s.replace('\\', "\\\\")
-            .replace(' ', "\\s")
-            .replace('/', "\\/")
+        escape::escape(s)
     }
 
+    #[allow(unused)]
     pub async fn connect(server: &str, port: u16) -> anyhow::Result<Self> {
-        let conn = TcpStream::connect(format!("{server}:{port}"))
-            .await
-            .map_err(|e| anyhow!("Got error while connect to {server}:{port} {e:?}"))?;
+        Self::connect_with_timeout(server, port, DEFAULT_READ_TIMEOUT).await
+    }
+
+    /// `read_timeout` is how long to wait for next chunk of response, increase it for bulk
+    /// queries on slow link.
+    #[allow(unused)]
+    pub async fn connect_with_timeout(
+        server: &str,
+        port: u16,
+        read_timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        Self::from_endpoint(Endpoint::Tcp(server.to_string(), port), read_timeout, None).await
+    }
+
+    /// Delay commands to stay under `commands_per_second`, avoid being banned by anti-flood
+    /// (error 3329). Set `flood_exempt` if login is in server's `query_ip_allowlist.txt`, those
+    /// are not rate limited by server so limiter is skipped.
+    pub async fn connect_with_rate_limit(
+        server: &str,
+        port: u16,
+        commands_per_second: f64,
+        flood_exempt: bool,
+    ) -> anyhow::Result<Self> {
+        Self::from_endpoint(
+            Endpoint::Tcp(server.to_string(), port),
+            DEFAULT_READ_TIMEOUT,
+            (!flood_exempt).then(|| RateLimiter::new(commands_per_second)),
+        )
+        .await
+    }
+
+    /// Connect to ServerQuery over SSH (port 10022 by default), authenticated by SSH so `login`
+    /// is not needed.
+    #[cfg(feature = "ssh")]
+    pub async fn connect_ssh(
+        server: &str,
+        port: u16,
+        user: &str,
+        password: &str,
+    ) -> anyhow::Result<Self> {
+        Self::from_endpoint(
+            Endpoint::Ssh {
+                server: server.to_string(),
+                port,
+                user: user.to_string(),
+                password: password.to_string(),
+            },
+            DEFAULT_READ_TIMEOUT,
+            None,
+        )
+        .await
+    }
+
+    /// See [`Self::connect_with_rate_limit`].
+    #[allow(unused)]
+    pub fn set_rate_limit(&mut self, commands_per_second: f64, flood_exempt: bool) {
+        self.rate_limiter = (!flood_exempt).then(|| RateLimiter::new(commands_per_second));
+    }
 
-        //let bufreader = BufReader::new(conn);
-        //conn.set_nonblocking(true).unwrap();
-        let mut self_ = Self { conn };
+    #[cfg(unix)]
+    #[allow(unused)]
+    pub async fn connect_unix(path: &Path) -> anyhow::Result<Self> {
+        Self::from_endpoint(
+            Endpoint::Unix(path.to_path_buf()),
+            DEFAULT_READ_TIMEOUT,
+            None,
+        )
+        .await
+    }
+
+    async fn from_endpoint(
+        endpoint: Endpoint,
+        read_timeout: Duration,
+        rate_limiter: Option<RateLimiter>,
+    ) -> anyhow::Result<Self> {
+        let mut self_ = Self {
+            conn: endpoint.open().await?,
+            endpoint,
+            session: Session::default(),
+            connection_lost: false,
+            client_id: None,
+            client_database_id: None,
+            verbose: false,
+            uid_cache: HashMap::new(),
+            display_name_cache: HashMap::new(),
+            permission_names: HashMap::new(),
+            last_server_message: None,
+            read_timeout,
+            rate_limiter,
+        };
+        self_.read_welcome().await?;
+        Ok(self_)
+    }
 
-        let content = self_
-            .read_data()
+    async fn read_welcome(&mut self) -> anyhow::Result<()> {
+        let endpoint = self.endpoint.clone();
+        let content = self
+            .read_until(|data| endpoint.welcome_complete(data))
             .await
             .map_err(|e| anyhow!("Got error in connect while read content: {e:?}"))?;
 
         if content.is_none() {
             warn!("Read none data.");
         }
+        Ok(())
+    }
 
-        Ok(self_)
+    /// Set once read or write failed with I/O error or server closed the connection.
+    pub fn connection_lost(&self) -> bool {
+        self.connection_lost
+    }
+
+    /// Open a new connection to the same endpoint, then replay login, `use`, nickname and
+    /// notify registrations of the old one. Caches of client ids are kept, they are server side
+    /// data and still valid.
+    pub async fn reconnect(&mut self) -> anyhow::Result<()> {
+        self.conn = self.endpoint.open().await?;
+        self.connection_lost = false;
+        self.client_id = None;
+        self.client_database_id = None;
+        self.read_welcome().await?;
+
+        let session = self.session.clone();
+        if let Some((user, password)) = &session.credentials {
+            self.login(user, password)
+                .await
+                .map_err(|e| anyhow!("Login failed after reconnect: {e:?}"))?;
+        }
+        if let Some(server_id) = session.server_id {
+            self.select_server(server_id)
+                .await
+                .map_err(|e| anyhow!("Select server failed after reconnect: {e:?}"))?;
+        }
+        if let Some(nickname) = &session.nickname {
+            // Old session may still hold the nickname until server notice the timeout
+            self.change_nickname(nickname)
+                .await
+                .inspect_err(|e| warn!("Restore nickname failed after reconnect: {e:?}"))
+                .ok();
+        }
+        // Query before notifies registered, so no notify could be mixed into responses
+        self.who_am_i()
+            .await
+            .map_err(|e| anyhow!("Query whoami failed after reconnect: {e:?}"))?;
+        if let Some((channel_id, password)) = &session.channel {
+            // Channel may be deleted or password changed while disconnected
+            self.set_own_channel(*channel_id, password.as_deref())
+                .await
+                .inspect_err(|e| {
+                    warn!("Move back to channel {channel_id} failed after reconnect: {e:?}")
+                })
+                .ok();
+        }
+        for payload in &session.notify_payloads {
+            self.basic_operation(payload)
+                .await
+                .map_err(|e| anyhow!("Register notify failed after reconnect: {e:?}"))?;
+        }
+        Ok(())
     }
 
     pub async fn login(&mut self, user: &str, password: &str) -> QueryResult<()> {
         let payload = format!("login {user} {password}\n\r");
-        self.basic_operation(payload.as_str()).await
+        self.basic_operation(payload.as_str()).await?;
+        self.session.credentials = Some((user.to_string(), password.to_string()));
+        Ok(())
     }
 
     pub async fn select_server(&mut self, server_id: i64) -> QueryResult<()> {
         let payload = format!("use {server_id}\n\r");
-        self.basic_operation(payload.as_str()).await
+        self.basic_operation(payload.as_str()).await?;
+        self.session.server_id = Some(server_id);
+        Ok(())
     }
 
     pub(crate) async fn who_am_i(&mut self) -> QueryResult<WhoAmI> {
-        self.query_operation_non_error("whoami\n\r")
+        let who_am_i: WhoAmI = self
+            .query_operation_non_error("whoami\n\r")
             .await
-            .map(|mut v| v.remove(0))
+            .map(|mut v| v.remove(0))?;
+        self.client_id = Some(who_am_i.client_id());
+        self.client_database_id = Some(who_am_i.client_database_id());
+        Ok(who_am_i)
+    }
+
+    /// Measure a single `whoami` round-trip.
+    pub async fn ping(&mut self) -> QueryResult<Duration> {
+        let start = tokio::time::Instant::now();
+        self.basic_operation("whoami\n\r").await?;
+        Ok(start.elapsed())
+    }
+
+    /// Ping `count` times, warn if the standard deviation (in milliseconds) exceeds `std_dev_threshold`.
+    #[allow(unused)]
+    pub async fn ping_with_jitter_measurement(
+        &mut self,
+        count: usize,
+        std_dev_threshold: f64,
+    ) -> QueryResult<PingResult> {
+        let mut samples = Vec::with_capacity(count);
+        for _ in 0..count.max(1) {
+            samples.push(self.ping().await?);
+        }
+        let result = PingResult::from_samples(samples);
+        if result.std_dev() > std_dev_threshold {
+            warn!(
+                "Unstable connection, latency standard deviation {:.2}ms exceed {:.2}ms (mean {:?})",
+                result.std_dev(),
+                std_dev_threshold,
+                result.mean()
+            );
+        }
+        Ok(result)
+    }
+
+    /// ServerQuery has no command to echo input back, so verbose mode is done on our side by
+    /// logging every outgoing payload exactly as written to the socket.
+    #[allow(unused)]
+    pub async fn enable_verbose_mode(&mut self) -> QueryResult<()> {
+        self.verbose = true;
+        Ok(())
+    }
+
+    #[allow(unused)]
+    pub async fn disable_verbose_mode(&mut self) -> QueryResult<()> {
+        self.verbose = false;
+        Ok(())
+    }
+
+    /// Client id stored by the most recent successful [`Self::who_am_i`] call.
+    pub fn cached_client_id(&self) -> Option<i64> {
+        self.client_id
+    }
+
+    /// Move query client itself out of the server query pseudo channel, so it is visible in
+    /// `channel_id`. Channel is moved back into after [`Self::reconnect`].
+    pub async fn set_own_channel(
+        &mut self,
+        channel_id: i64,
+        channel_password: Option<&str>,
+    ) -> QueryResult<()> {
+        let client_id = match self.client_id {
+            Some(client_id) => client_id,
+            None => self.who_am_i().await?.client_id(),
+        };
+        let mut payload = format!("clientmove clid={client_id} cid={channel_id}");
+        if let Some(password) = channel_password {
+            payload.push_str(&format!(" cpw={}", Self::escape(password)));
+        }
+        payload.push_str("\n\r");
+        self.basic_operation(&payload).await?;
+        self.session.channel = Some((channel_id, channel_password.map(ToString::to_string)));
+        Ok(())
+    }
+
+    async fn self_database_id(&mut self) -> QueryResult<i64> {
+        match self.client_database_id {
+            Some(client_database_id) => Ok(client_database_id),
+            None => Ok(self.who_am_i().await?.client_database_id()),
+        }
+    }
+
+    pub async fn add_self_to_server_group(&mut self, sgid: i64) -> QueryResult<()> {
+        let client_database_id = self.self_database_id().await?;
+        self.server_group_add_client(sgid, client_database_id).await
+    }
+
+    pub async fn remove_self_from_server_group(&mut self, sgid: i64) -> QueryResult<()> {
+        let client_database_id = self.self_database_id().await?;
+        self.server_group_del_client(sgid, client_database_id).await
+    }
+
+    /// Query `whoami` first, then fill in identity and server groups from `clientinfo`.
+    #[allow(unused)]
+    pub async fn who_am_i_extended(&mut self) -> QueryResult<SelfInfo> {
+        let who_am_i = self.who_am_i().await?;
+        self.query_one_operation::<SelfInfo>(&format!(
+            "clientinfo clid={}\n\r",
+            who_am_i.client_id()
+        ))
+        .await?
+        .map(|info| info.merge(&who_am_i))
+        .ok_or_else(QueryError::static_empty_response)
+    }
+
+    /// Empty message is rejected locally, server would answer error 1540 anyway.
+    #[allow(unused)]
+    pub async fn poke_client(&mut self, client_id: i64, msg: &str) -> QueryResult<()> {
+        if msg.is_empty() {
+            return Err(anyhow!("Poke message can't be empty").into());
+        }
+        self.basic_operation(&format!(
+            "clientpoke clid={client_id} msg={}\n\r",
+            Self::escape(msg)
+        ))
+        .await
     }
 
+    /// Priority speaker's voice is boosted over others in the channel.
     #[allow(unused)]
+    pub async fn set_priority_speaker(&mut self, client_id: i64, enable: bool) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "clientedit clid={client_id} client_is_priority_speaker={}\n\r",
+            enable as u8
+        ))
+        .await
+    }
+
     pub(crate) async fn send_text_message(
         &mut self,
         client_id: i64,
@@ -196,6 +959,46 @@ impl SocketConn {
         self.basic_operation(&payload).await
     }
 
+    /// The server delivers channel messages to the channel where query client currently is,
+    /// `target` is ignored. Move into `channel_id` before calling this. Every channel accepts
+    /// text messages regardless of its codec, there is no need to filter targets by codec.
+    pub(crate) async fn send_channel_message(
+        &mut self,
+        channel_id: i64,
+        text: &str,
+    ) -> QueryResult<()> {
+        let payload = format!(
+            "sendtextmessage targetmode=2 target={channel_id} msg={text}\n\r",
+            text = Self::escape(text)
+        );
+        self.basic_operation(&payload).await
+    }
+
+    /// Broadcast to whole virtual server. Server broadcasts are rate limited, calls within
+    /// [`SERVER_MESSAGE_INTERVAL`] of previous one wait until the interval passes.
+    #[allow(unused)]
+    pub async fn send_server_message(&mut self, text: &str) -> QueryResult<()> {
+        if let Some(last) = self.last_server_message {
+            tokio::time::sleep_until(last + SERVER_MESSAGE_INTERVAL).await;
+        }
+        let payload = format!(
+            "sendtextmessage targetmode=3 target=0 msg={}\n\r",
+            Self::escape(text)
+        );
+        let ret = self.basic_operation(&payload).await;
+        self.last_server_message = Some(Instant::now());
+        ret
+    }
+
+    #[allow(unused)]
+    pub async fn send_text_message_formatted(
+        &mut self,
+        client_id: i64,
+        message: TextMessage,
+    ) -> QueryResult<()> {
+        self.send_text_message(client_id, &message.build()).await
+    }
+
     pub(crate) async fn send_text_message_unchecked(
         &mut self,
         client_id: i64,
@@ -215,43 +1018,454 @@ impl SocketConn {
             .map(|mut v| v.remove(0))
     }
 
-    pub(crate) async fn query_channels(&mut self) -> QueryResult<Vec<Channel>> {
-        self.query_operation_non_error("channellist\n\r").await
+    /// Quota used this month (`virtualserver_month_bytes_*`), also emitted as gauges.
+    #[allow(unused)]
+    pub async fn query_server_upload_quota(&mut self) -> QueryResult<QuotaInfo> {
+        let quota = self.query_server_info().await?.quota_info();
+        quota.emit();
+        Ok(quota)
     }
 
-    pub(crate) async fn create_channel(
-        &mut self,
-        name: &str,
-        pid: i64,
-    ) -> QueryResult<Option<CreateChannel>> {
-        let payload = format!(
-            "channelcreate channel_name={name} cpid={pid} channel_codec_quality=10\n\r",
-            name = Self::escape(name),
-            pid = pid
-        );
-        /*let ret = self.query_operation(payload.as_str()).await?;
-        Ok(ret.map(|mut v| v.remove(0)))*/
-        self.query_operation(payload.as_str())
+    /// Channel count from `serverinfo` (`virtualserver_channelsonline`), cheaper than
+    /// `channellist` on server with many channels.
+    #[allow(unused)]
+    pub async fn count_channels(&mut self) -> QueryResult<u32> {
+        self.query_server_info()
             .await
-            .map(|r| r.map(|mut v| v.swap_remove(0)))
+            .map(|info| info.channels_online())
     }
 
-    pub(crate) async fn query_clients(&mut self) -> QueryResult<Vec<Client>> {
-        self.query_operation_non_error("clientlist\n\r").await
+    /// Online client count from `serverinfo` (`virtualserver_clientsonline`), query clients
+    /// included, use it instead of `clientlist` when only headcount is needed.
+    #[allow(unused)]
+    pub async fn count_online_clients(&mut self) -> QueryResult<u32> {
+        self.query_server_info()
+            .await
+            .map(|info| info.clients_online())
     }
 
-    pub(crate) async fn move_client(
-        &mut self,
-        client_id: i64,
-        target_channel: i64,
-    ) -> QueryResult<()> {
-        let payload = format!(
-            "clientmove clid={client_id} cid={cid}\n\r",
-            client_id = client_id,
-            cid = target_channel
-        );
-        self.basic_operation(payload.as_str()).await
-    }
+    /// Online query client count from `serverinfo` (`virtualserver_queryclientsonline`).
+    #[allow(unused)]
+    pub async fn count_online_query_clients(&mut self) -> QueryResult<u32> {
+        self.query_server_info()
+            .await
+            .map(|info| info.query_clients_online())
+    }
+
+    #[allow(unused)]
+    pub async fn query_transfer_quotas(&mut self) -> QueryResult<TransferQuotas> {
+        self.query_server_info()
+            .await
+            .map(|info| info.transfer_quotas())
+    }
+
+    pub async fn query_version(&mut self) -> QueryResult<VersionInfo> {
+        self.query_one_operation("version\n\r")
+            .await?
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    /// `-short` would drop the client counts and uptime, so it is not used. `serverlist` has no
+    /// bandwidth information, use `serverinfo` of each server for that.
+    #[allow(unused)]
+    pub async fn query_virtual_servers_with_stats(
+        &mut self,
+    ) -> QueryResult<Vec<VirtualServerWithStats>> {
+        self.query_operation_non_error("serverlist -uid\n\r").await
+    }
+
+    pub async fn query_host_info(&mut self) -> QueryResult<HostInfo> {
+        self.query_operation_non_error("hostinfo\n\r")
+            .await
+            .map(|mut v| v.remove(0))
+    }
+
+    /// Write `message` into the virtual server log.
+    #[allow(unused)]
+    pub async fn log_message(&mut self, level: LogLevel, message: &str) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "logadd loglevel={} logmsg={}\n\r",
+            level.value(),
+            Self::escape(message)
+        ))
+        .await
+    }
+
+    /// Host message is a virtual server property, so this goes through `serveredit`
+    /// rather than `instanceedit`.
+    #[allow(unused)]
+    pub async fn set_host_message(
+        &mut self,
+        mode: HostMessageMode,
+        message: &str,
+    ) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "serveredit virtualserver_hostmessage_mode={} virtualserver_hostmessage={}\n\r",
+            mode.value(),
+            Self::escape(message)
+        ))
+        .await
+    }
+
+    /// `virtualserver_hostbanner_*` belong to the selected virtual server (`serveredit`).
+    /// `gfx_interval` is in seconds, 0 disables reloading.
+    #[allow(unused)]
+    pub async fn set_host_banner(
+        &mut self,
+        url: &str,
+        gfx_url: &str,
+        gfx_interval: u32,
+    ) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "serveredit virtualserver_hostbanner_url={} virtualserver_hostbanner_gfx_url={} virtualserver_hostbanner_gfx_interval={gfx_interval}\n\r",
+            Self::escape(url),
+            Self::escape(gfx_url),
+        ))
+        .await
+    }
+
+    pub async fn query_instance_settings(&mut self) -> QueryResult<InstanceSettings> {
+        self.query_one_operation("instanceinfo\n\r")
+            .await?
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    #[allow(unused)]
+    pub async fn query_default_groups(&mut self) -> QueryResult<DefaultGroups> {
+        self.query_instance_settings()
+            .await
+            .map(|settings| DefaultGroups::from(&settings))
+    }
+
+    /// Server side UNIX timestamp, compare with local time to detect clock skew.
+    #[allow(unused)]
+    pub async fn query_server_time(&mut self) -> QueryResult<i64> {
+        self.query_host_info()
+            .await
+            .map(|info| info.host_timestamp_utc())
+    }
+
+    pub(crate) async fn query_channels(&mut self) -> QueryResult<Vec<Channel>> {
+        self.query_operation_non_error("channellist\n\r").await
+    }
+
+    #[allow(unused)]
+    pub async fn query_spacer_channels(&mut self) -> QueryResult<Vec<Channel>> {
+        self.query_channels().await.map(|channels| {
+            channels
+                .into_iter()
+                .filter(|channel| channel.is_spacer())
+                .collect()
+        })
+    }
+
+    #[allow(unused)]
+    pub async fn query_non_spacer_channels(&mut self) -> QueryResult<Vec<Channel>> {
+        self.query_channels().await.map(|channels| {
+            channels
+                .into_iter()
+                .filter(|channel| !channel.is_spacer())
+                .collect()
+        })
+    }
+
+    pub async fn query_channels_with_limits(&mut self) -> QueryResult<Vec<ChannelWithLimits>> {
+        self.query_operation_non_error("channellist -limits\n\r")
+            .await
+    }
+
+    /// Channels created by auto channel, i.e. existing channels which id is stored in `kv`
+    /// for `server_id` (virtual server unique identifier).
+    #[allow(unused)]
+    pub async fn query_auto_channels(
+        &mut self,
+        kv: &mut dyn KVMap,
+        server_id: &str,
+    ) -> QueryResult<Vec<i64>> {
+        let mut stored = Vec::new();
+        for key in kv
+            .list_keys(&format!("ts_autochannel_*_{server_id}_*"))
+            .await?
+        {
+            if let Some(channel_id) = kv.get(key).await?.and_then(|v| v.parse::<i64>().ok()) {
+                stored.push(channel_id);
+            }
+        }
+        Ok(self
+            .query_channels()
+            .await?
+            .iter()
+            .map(|channel| channel.cid())
+            .filter(|channel_id| stored.contains(channel_id))
+            .collect())
+    }
+
+    /// Server return error id 768 instead of empty list if no channel name contains `pattern`.
+    #[allow(unused)]
+    pub async fn find_channel(&mut self, pattern: &str) -> QueryResult<Vec<ChannelMatch>> {
+        match self
+            .query_operation(&format!(
+                "channelfind pattern={}\n\r",
+                Self::escape(pattern)
+            ))
+            .await
+        {
+            Ok(ret) => Ok(ret.unwrap_or_default()),
+            Err(e) if e.code() == 768 => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[allow(unused)]
+    pub async fn query_channels_with_flags(&mut self) -> QueryResult<Vec<ChannelWithFlags>> {
+        self.query_operation_non_error("channellist -flags\n\r")
+            .await
+    }
+
+    #[allow(unused)]
+    pub async fn query_channels_with_topics(&mut self) -> QueryResult<Vec<ChannelWithTopic>> {
+        self.query_operation_non_error("channellist -topic\n\r")
+            .await
+    }
+
+    /// `channellist` has no switch for descriptions, so `channelinfo` of every channel is
+    /// pipelined instead.
+    #[allow(unused)]
+    pub async fn query_channels_with_descriptions(
+        &mut self,
+    ) -> QueryResult<Vec<ChannelWithDescription>> {
+        let channels = self.query_channels().await?;
+        let mut ret = Vec::with_capacity(channels.len());
+        for batch in channels.chunks(PIPELINE_BATCH_SIZE) {
+            let payloads = batch
+                .iter()
+                .map(|channel| format!("channelinfo cid={}\n\r", channel.cid()))
+                .collect::<Vec<_>>();
+            for (channel, response) in batch.iter().zip(self.pipeline_operation(&payloads).await?) {
+                let description = Self::decode_status_with_result::<ChannelInfo>(response)?
+                    .and_then(|mut info| info.pop())
+                    .map(|info| info.channel_description().to_string())
+                    .unwrap_or_default();
+                ret.push(ChannelWithDescription::new(channel.clone(), description));
+            }
+        }
+        Ok(ret)
+    }
+
+    #[allow(unused)]
+    pub async fn query_channels_with_voice_info(&mut self) -> QueryResult<Vec<ChannelWithVoice>> {
+        self.query_operation_non_error("channellist -voice\n\r")
+            .await
+    }
+
+    pub async fn query_channel_info(
+        &mut self,
+        channel_id: i64,
+    ) -> QueryResult<Option<ChannelInfo>> {
+        self.query_one_operation(&format!("channelinfo cid={channel_id}\n\r"))
+            .await
+    }
+
+    #[allow(unused)]
+    pub async fn query_channel_name(&mut self, channel_id: i64) -> QueryResult<String> {
+        self.query_one_operation::<ChannelName>(&format!("channelinfo cid={channel_id}\n\r"))
+            .await?
+            .map(|name| name.channel_name().to_string())
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    pub(crate) async fn create_channel(
+        &mut self,
+        name: &str,
+        pid: i64,
+    ) -> QueryResult<Option<CreateChannel>> {
+        let payload = format!(
+            "channelcreate channel_name={name} cpid={pid} channel_codec_quality=10\n\r",
+            name = Self::escape(name),
+            pid = pid
+        );
+        /*let ret = self.query_operation(payload.as_str()).await?;
+        Ok(ret.map(|mut v| v.remove(0)))*/
+        self.query_operation(payload.as_str())
+            .await
+            .map(|r| r.map(|mut v| v.swap_remove(0)))
+    }
+
+    /// Without `force`, server refuse to delete channel that still has clients, check it with
+    /// [`QueryError::is_channel_not_empty`].
+    #[allow(unused)]
+    pub async fn delete_channel(&mut self, channel_id: i64, force: bool) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "channeldelete cid={channel_id} force={}\n\r",
+            force as u8
+        ))
+        .await
+    }
+
+    pub(crate) async fn query_clients(&mut self) -> QueryResult<Vec<Client>> {
+        self.query_operation_non_error("clientlist\n\r").await
+    }
+
+    #[allow(unused)]
+    pub async fn query_clients_with_badges(&mut self) -> QueryResult<Vec<ClientWithBadges>> {
+        self.query_operation_non_error("clientlist -badges\n\r")
+            .await
+    }
+
+    /// `connection_client_ip` is only returned to connections with serveradmin privileges.
+    #[allow(unused)]
+    pub async fn query_clients_with_ips(&mut self) -> QueryResult<Vec<ClientWithIp>> {
+        self.query_operation_non_error("clientlist -ip\n\r").await
+    }
+
+    /// Move client only if [`ClientInfo::is_client_muted`], return whether client was moved.
+    ///
+    /// `clientmove` is issued right after `clientinfo` returns, to keep the window in which
+    /// client may unmute small.
+    pub async fn move_client_if_muted(
+        &mut self,
+        client_id: i64,
+        target_channel: i64,
+    ) -> QueryResult<bool> {
+        let muted = self
+            .query_client_info(client_id)
+            .await?
+            .is_some_and(|info| info.is_client_muted());
+        if muted {
+            self.move_client(client_id, target_channel).await?;
+        }
+        Ok(muted)
+    }
+
+    pub(crate) async fn move_client(
+        &mut self,
+        client_id: i64,
+        target_channel: i64,
+    ) -> QueryResult<()> {
+        let payload = format!(
+            "clientmove clid={client_id} cid={cid}\n\r",
+            client_id = client_id,
+            cid = target_channel
+        );
+        self.basic_operation(payload.as_str()).await
+    }
+
+    /// Move every user in `source_channel` with a single `clientmove`, return moved count.
+    #[allow(unused)]
+    pub async fn move_all_channel_clients(
+        &mut self,
+        source_channel: i64,
+        target_channel: i64,
+    ) -> QueryResult<u32> {
+        let client_ids = self
+            .query_clients()
+            .await?
+            .into_iter()
+            .filter(|client| client.channel_id() == source_channel && client.client_is_user())
+            .map(|client| format!("clid={}", client.client_id()))
+            .collect::<Vec<_>>();
+        if client_ids.is_empty() {
+            return Ok(0);
+        }
+        self.basic_operation(&format!(
+            "clientmove {} cid={target_channel}\n\r",
+            client_ids.join("|")
+        ))
+        .await?;
+        Ok(client_ids.len() as u32)
+    }
+
+    /// Kick another ServerQuery connection (e.g. a stale bot session) off the server,
+    /// require serveradmin permission.
+    #[allow(unused)]
+    pub async fn disconnect_query_client(&mut self, client_id: i64) -> QueryResult<()> {
+        self.kick_client(client_id, 5, "").await
+    }
+
+    /// `reason_id` 4 kick from channel, 5 kick from server, anything else is rejected.
+    pub async fn kick_client(
+        &mut self,
+        client_id: i64,
+        reason_id: u8,
+        reason_msg: &str,
+    ) -> QueryResult<()> {
+        if !matches!(reason_id, 4 | 5) {
+            return Err(anyhow!(
+                "Invalid kick reason id {reason_id}, expect 4 (channel) or 5 (server)"
+            )
+            .into());
+        }
+        let mut payload = format!("clientkick clid={client_id} reasonid={reason_id}");
+        if !reason_msg.is_empty() {
+            payload.push_str(&format!(" reasonmsg={}", Self::escape(reason_msg)));
+        }
+        payload.push_str("\n\r");
+        self.basic_operation(&payload).await
+    }
+
+    /// `None` means unlimited.
+    #[allow(unused)]
+    pub async fn set_channel_max_clients(
+        &mut self,
+        channel_id: i64,
+        max: Option<i32>,
+    ) -> QueryResult<()> {
+        let payload = match max {
+            Some(max) => format!(
+                "channeledit cid={channel_id} channel_flag_maxclients_unlimited=0 channel_maxclients={max}\n\r"
+            ),
+            None => format!(
+                "channeledit cid={channel_id} channel_flag_maxclients_unlimited=1 channel_maxclients=-1\n\r"
+            ),
+        };
+        self.basic_operation(&payload).await
+    }
+
+    /// Edit channel properties, e.g. `channel_name`, `channel_description`, `channel_maxclients`
+    /// or `channel_flag_permanent`. Values are escaped, keys are sent as is.
+    #[allow(unused)]
+    pub async fn edit_channel(
+        &mut self,
+        channel_id: i64,
+        properties: &[(&str, String)],
+    ) -> QueryResult<()> {
+        if properties.is_empty() {
+            return Ok(());
+        }
+        let payload = properties
+            .iter()
+            .map(|(key, value)| format!("{key}={}", Self::escape(value)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.basic_operation(&format!("channeledit cid={channel_id} {payload}\n\r"))
+            .await
+    }
+
+    /// Pipelined `channeledit` for every channel, failed edit doesn't abort the rest. Entries
+    /// with empty properties are skipped and left out of the result.
+    #[allow(unused)]
+    pub async fn edit_channels_batch(
+        &mut self,
+        edits: &[(i64, ChannelProperties)],
+    ) -> QueryResult<Vec<(i64, QueryResult<()>)>> {
+        let edits = edits
+            .iter()
+            .filter(|(_, properties)| !properties.is_empty())
+            .collect::<Vec<_>>();
+        let mut ret = Vec::with_capacity(edits.len());
+        for batch in edits.chunks(PIPELINE_BATCH_SIZE) {
+            let payloads = batch
+                .iter()
+                .map(|(channel_id, properties)| properties.payload(*channel_id))
+                .collect::<Vec<_>>();
+            for ((channel_id, _), response) in
+                batch.iter().zip(self.pipeline_operation(&payloads).await?)
+            {
+                ret.push((*channel_id, Self::decode_status(response).map(|_| ())));
+            }
+        }
+        Ok(ret)
+    }
 
     pub(crate) async fn set_client_channel_group(
         &mut self,
@@ -268,6 +1482,26 @@ impl SocketConn {
         self.basic_operation(&payload).await
     }
 
+    /// Set many `(client_database_id, channel_id, group_id)` in a single command.
+    #[allow(unused)]
+    pub async fn set_client_channel_groups_bulk(
+        &mut self,
+        assignments: &[(i64, i64, i64)],
+    ) -> QueryResult<()> {
+        if assignments.is_empty() {
+            return Ok(());
+        }
+        let payload = assignments
+            .iter()
+            .map(|(client_database_id, channel_id, group_id)| {
+                format!("cgid={group_id} cid={channel_id} cldbid={client_database_id}")
+            })
+            .collect::<Vec<_>>()
+            .join("|");
+        self.basic_operation(&format!("setclientchannelgroup {payload}\n\r"))
+            .await
+    }
+
     pub(crate) async fn add_channel_permission(
         &mut self,
         target_channel: i64,
@@ -284,58 +1518,1214 @@ impl SocketConn {
         self.basic_operation(&payload).await
     }
 
-    pub async fn send_keepalive(&mut self) -> QueryResult<()> {
-        self.write_data("whoami\n\rbanlist\n\r")
-            .await
-            .map_err(QueryError::from)
-    }
-
-    pub(crate) async fn logout(&mut self) -> QueryResult<()> {
-        self.basic_operation("quit\n\r").await
-    }
-
-    pub async fn register_observer_events(&mut self) -> QueryResult<()> {
-        self.basic_operation("servernotifyregister event=server\n\r")
+    /// Replace all permissions of `target_channel_id` with permissions of `source_channel_id`.
+    #[allow(unused)]
+    pub async fn copy_channel_permissions(
+        &mut self,
+        source_channel_id: i64,
+        target_channel_id: i64,
+    ) -> QueryResult<()> {
+        let source: Vec<Permission> = self
+            .query_operation_or_empty(&format!("channelpermlist cid={source_channel_id}\n\r"))
             .await?;
-        self.basic_operation("servernotifyregister event=textprivate\n\r")
-            .await
-    }
-
-    /// As http://yat.qa/ressourcen/server-query-notify/ said:
-    ///
-    /// Man kann nur ein Channel-Abo haben. Es gilt das erste, das man abonniert hat. Dies wird nur
-    /// durch Verlassen des Servers oder servernotifyunregister zurückgesetzt.
-    /// Insbesondere wird es nicht zurückgesetzt, wenn der Channel gelöscht wird. Arrays als
-    /// Parameter sind nicht möglich. Beim Löschen eines Channels
-    /// geht das Abonnement nicht verloren.
-    pub async fn register_channel_events(&mut self) -> QueryResult<()> {
-        self.basic_operation("servernotifyregister event=channel id=0\n\r")
-            .await
-    }
-
-    pub async fn change_nickname(&mut self, nickname: &str) -> QueryResult<()> {
+        let target: Vec<Permission> = self
+            .query_operation_or_empty(&format!("channelpermlist cid={target_channel_id}\n\r"))
+            .await?;
+        if !target.is_empty() {
+            let payload = target
+                .iter()
+                .map(|p| format!("permid={}", p.permission_id()))
+                .collect::<Vec<_>>()
+                .join("|");
+            self.basic_operation(&format!(
+                "channeldelperm cid={target_channel_id} {payload}\n\r"
+            ))
+            .await?;
+        }
+        if source.is_empty() {
+            return Ok(());
+        }
+        let payload = source
+            .iter()
+            .map(|p| {
+                format!(
+                    "permid={} permvalue={} permnegated={} permskip={}",
+                    p.permission_id(),
+                    p.permission_value(),
+                    p.negated() as u8,
+                    p.skip() as u8
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("|");
         self.basic_operation(&format!(
-            "clientupdate client_nickname={}\n\r",
-            Self::escape(nickname)
+            "channeladdperm cid={target_channel_id} {payload}\n\r"
         ))
         .await
     }
 
-    pub(crate) async fn client_get_database_id_from_uid(
+    #[allow(unused)]
+    pub async fn add_channel_group_permission(
         &mut self,
-        uid: &str,
-    ) -> QueryResult<DatabaseId> {
-        self.query_operation_non_error(&format!("clientgetdbidfromuid cluid={uid}\n\r"))
-            .await
-            .map(|mut v| v.remove(0))
-    }
-
-    pub async fn ban_del(&mut self, ban_id: i64) -> QueryResult<()> {
+        cgid: i64,
+        permissions: &[Permission],
+    ) -> QueryResult<()> {
+        let payload = format!(
+            "channelgroupaddperm cgid={cgid} {}\n\r",
+            permissions
+                .iter()
+                .map(|p| format!(
+                    "permid={} permvalue={}",
+                    p.permission_id(),
+                    p.permission_value()
+                ))
+                .collect::<Vec<String>>()
+                .join("|")
+        );
+        self.basic_operation(&payload).await
+    }
+
+    #[allow(unused)]
+    pub async fn del_channel_group_permission(
+        &mut self,
+        cgid: i64,
+        permission_ids: &[u64],
+    ) -> QueryResult<()> {
+        let payload = format!(
+            "channelgroupdelperm cgid={cgid} {}\n\r",
+            permission_ids
+                .iter()
+                .map(|k| format!("permid={k}"))
+                .collect::<Vec<String>>()
+                .join("|")
+        );
+        self.basic_operation(&payload).await
+    }
+
+    /// Copy channel group, set `target_cgid` to 0 to create a new group.
+    #[allow(unused)]
+    pub async fn copy_channel_group(
+        &mut self,
+        source_cgid: i64,
+        target_cgid: i64,
+        name: &str,
+        group_type: ServerGroupType,
+    ) -> QueryResult<i64> {
+        let payload = format!(
+            "channelgroupcopy scgid={source_cgid} tcgid={target_cgid} name={name} type={group_type}\n\r",
+            name = Self::escape(name),
+            group_type = group_type.value()
+        );
+        self.query_one_operation::<CreatedChannelGroup>(&payload)
+            .await
+            .map(|ret| ret.map_or(target_cgid, |group| group.cgid()))
+    }
+
+    pub async fn query_permission_definitions(&mut self) -> QueryResult<Vec<PermissionDefinition>> {
+        self.query_operation_non_error("permissionlist\n\r")
+            .await
+            .map(|v: Vec<PermissionDefinition>| {
+                v.into_iter()
+                    .filter(|definition| !definition.is_group_marker())
+                    .collect()
+            })
+    }
+
+    /// Channel permissions with names resolved from `permissionlist`, which is only fetched once
+    /// per connection.
+    #[allow(unused)]
+    pub async fn query_channel_permissions_named(
+        &mut self,
+        channel_id: i64,
+    ) -> QueryResult<Vec<NamedPermission>> {
+        if self.permission_names.is_empty() {
+            self.permission_names = self
+                .query_permission_definitions()
+                .await?
+                .into_iter()
+                .map(|definition| {
+                    (
+                        definition.permission_id(),
+                        definition.permission_name().to_string(),
+                    )
+                })
+                .collect();
+        }
+        let permissions: Vec<Permission> = self
+            .query_operation_or_empty(&format!("channelpermlist cid={channel_id}\n\r"))
+            .await?;
+        Ok(permissions
+            .iter()
+            .map(|permission| {
+                let name = self
+                    .permission_names
+                    .get(&permission.permission_id())
+                    .cloned()
+                    .unwrap_or_else(|| permission.permission_id().to_string());
+                NamedPermission::new(permission, name)
+            })
+            .collect())
+    }
+
+    /// Permission name to permission id lookup table, permission id may differ between server
+    /// versions.
+    #[allow(unused)]
+    pub async fn query_permission_id_table(&mut self) -> QueryResult<HashMap<String, u64>> {
+        self.query_permission_definitions().await.map(|v| {
+            v.into_iter()
+                .map(|definition| {
+                    (
+                        definition.permission_name().to_string(),
+                        definition.permission_id(),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    /// Effective permissions of client in channel, `permoverview` require database id
+    /// instead of client id.
+    #[allow(unused)]
+    pub async fn query_effective_permissions(
+        &mut self,
+        client_database_id: i64,
+        channel_id: i64,
+    ) -> QueryResult<Vec<Permission>> {
+        self.query_operation_non_error(&format!(
+            "permoverview cldbid={client_database_id} cid={channel_id} permid=0\n\r"
+        ))
+        .await
+    }
+
+    /// Query permissions of every channel, `progress` is called with (finished, total) after
+    /// each pipelined batch.
+    #[allow(unused)]
+    pub async fn query_all_channel_permissions(
+        &mut self,
+        progress: impl FnMut(usize, usize),
+    ) -> QueryResult<HashMap<i64, Vec<Permission>>> {
+        let channel_ids = self
+            .query_channels()
+            .await?
+            .iter()
+            .map(|channel| channel.cid())
+            .collect::<Vec<_>>();
+        self.pipeline_channel_permissions(&channel_ids, PIPELINE_BATCH_SIZE, progress)
+            .await
+    }
+
+    /// Query permissions of `channel_ids`, every `batch_size` `channelpermlist` are written in
+    /// a single write.
+    #[allow(unused)]
+    pub async fn query_all_channel_permissions_pipelined(
+        &mut self,
+        channel_ids: &[i64],
+        batch_size: usize,
+    ) -> QueryResult<HashMap<i64, Vec<Permission>>> {
+        self.pipeline_channel_permissions(channel_ids, batch_size, |_, _| {})
+            .await
+    }
+
+    async fn pipeline_channel_permissions(
+        &mut self,
+        channel_ids: &[i64],
+        batch_size: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> QueryResult<HashMap<i64, Vec<Permission>>> {
+        let mut ret = HashMap::new();
+        for batch in channel_ids.chunks(batch_size.max(1)) {
+            let payloads = batch
+                .iter()
+                .map(|channel_id| format!("channelpermlist cid={channel_id}\n\r"))
+                .collect::<Vec<_>>();
+            for (channel_id, response) in
+                batch.iter().zip(self.pipeline_operation(&payloads).await?)
+            {
+                let permissions = match Self::decode_status_with_result(response) {
+                    Ok(permissions) => permissions.unwrap_or_default(),
+                    Err(e) if e.code() == 1281 => Vec::new(),
+                    Err(e) => return Err(e),
+                };
+                ret.insert(*channel_id, permissions);
+            }
+            progress(ret.len(), channel_ids.len());
+        }
+        Ok(ret)
+    }
+
+    pub async fn server_group_add_client(
+        &mut self,
+        server_group_id: i64,
+        client_database_id: i64,
+    ) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "servergroupaddclient sgid={server_group_id} cldbid={client_database_id}\n\r"
+        ))
+        .await
+    }
+
+    pub async fn server_group_del_client(
+        &mut self,
+        server_group_id: i64,
+        client_database_id: i64,
+    ) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "servergroupdelclient sgid={server_group_id} cldbid={client_database_id}\n\r"
+        ))
+        .await
+    }
+
+    /// Add every client to server group `sgid`, one `servergroupaddclient` per client.
+    ///
+    /// Commands share one connection so they can't run concurrently, instead they are pipelined
+    /// in batches. A failed client doesn't abort the rest, only connection errors do.
+    #[allow(unused)]
+    pub async fn add_clients_to_server_group(
+        &mut self,
+        sgid: i64,
+        client_database_ids: &[i64],
+    ) -> QueryResult<Vec<(i64, QueryResult<()>)>> {
+        let mut ret = Vec::with_capacity(client_database_ids.len());
+        for batch in client_database_ids.chunks(PIPELINE_BATCH_SIZE) {
+            let payloads = batch
+                .iter()
+                .map(|client_database_id| {
+                    format!("servergroupaddclient sgid={sgid} cldbid={client_database_id}\n\r")
+                })
+                .collect::<Vec<_>>();
+            for (client_database_id, response) in
+                batch.iter().zip(self.pipeline_operation(&payloads).await?)
+            {
+                ret.push((
+                    *client_database_id,
+                    Self::decode_status(response).map(|_| ()),
+                ));
+            }
+        }
+        Ok(ret)
+    }
+
+    /// `path` is absolute in channel file browser, e.g. `/icon.png`. Return `None` if file does
+    /// not exist, which server reports as either 1281 (empty result set) or 2051 (file not
+    /// found) depending on version.
+    #[allow(unused)]
+    pub async fn query_file_info(
+        &mut self,
+        channel_id: i64,
+        path: &str,
+    ) -> QueryResult<Option<FileInfo>> {
+        match self
+            .query_one_operation(&format!(
+                "ftgetfileinfo cid={channel_id} cpw= name={}\n\r",
+                Self::escape(path)
+            ))
+            .await
+        {
+            Err(e) if e.code() == 1281 || e.code() == 2051 => Ok(None),
+            ret => ret,
+        }
+    }
+
+    pub async fn query_server_groups(&mut self) -> QueryResult<Vec<ServerGroup>> {
+        self.query_operation_non_error("servergrouplist\n\r").await
+    }
+
+    /// Sorted by `sortid` (then `sgid`) as TeamSpeak client displays them, `servergrouplist`
+    /// returns groups in creation order.
+    #[allow(unused)]
+    pub async fn query_server_groups_ordered(&mut self) -> QueryResult<Vec<ServerGroup>> {
+        let mut groups = self.query_server_groups().await?;
+        groups.sort_by_key(|group| (group.sort_id(), group.sgid()));
+        Ok(groups)
+    }
+
+    #[allow(unused)]
+    pub async fn query_server_group_permissions(
+        &mut self,
+        server_group_id: i64,
+    ) -> QueryResult<Vec<Permission>> {
+        self.query_operation_or_empty(&format!("servergrouppermlist sgid={server_group_id}\n\r"))
+            .await
+    }
+
+    /// Pipelined [`Self::query_server_group_permissions`] for every server group.
+    ///
+    /// Groups that fail to query are logged and left out of the result.
+    #[allow(unused)]
+    pub async fn export_all_group_permissions(
+        &mut self,
+    ) -> QueryResult<HashMap<i64, Vec<Permission>>> {
+        let groups = self.query_server_groups().await?;
+        let mut ret = HashMap::new();
+        for batch in groups.chunks(PIPELINE_BATCH_SIZE) {
+            let payloads = batch
+                .iter()
+                .map(|group| format!("servergrouppermlist sgid={}\n\r", group.sgid()))
+                .collect::<Vec<_>>();
+            for (group, response) in batch.iter().zip(self.pipeline_operation(&payloads).await?) {
+                match Self::decode_status_with_result(response) {
+                    Ok(permissions) => {
+                        ret.insert(group.sgid(), permissions.unwrap_or_default());
+                    }
+                    Err(e) if e.code() == 1281 => {
+                        ret.insert(group.sgid(), Vec::new());
+                    }
+                    Err(e) => warn!(
+                        "Unable export permissions of server group {:?}({}): {e:?}",
+                        group.name(),
+                        group.sgid()
+                    ),
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Send a single `whoami` without reading response. For connection which receives
+    /// notifies, reading response here would drop notifies arrived before it, so the response
+    /// must be consumed as ordinary lines by caller's read loop. Connection without notifies
+    /// should use [`Self::who_am_i`] instead.
+    pub async fn send_keepalive_unchecked(&mut self) -> QueryResult<()> {
+        self.write_data("whoami\n\r")
+            .await
+            .map_err(QueryError::from)
+    }
+
+    pub(crate) async fn logout(&mut self) -> QueryResult<()> {
+        self.basic_operation("quit\n\r").await
+    }
+
+    pub async fn register_observer_events(&mut self) -> QueryResult<()> {
+        self.notify_register("servernotifyregister event=server\n\r")
+            .await?;
+        self.notify_register("servernotifyregister event=textprivate\n\r")
+            .await
+    }
+
+    async fn notify_register(&mut self, payload: &str) -> QueryResult<()> {
+        self.basic_operation(payload).await?;
+        if !self.session.notify_payloads.iter().any(|p| p == payload) {
+            self.session.notify_payloads.push(payload.to_string());
+        }
+        Ok(())
+    }
+
+    /// As http://yat.qa/ressourcen/server-query-notify/ said:
+    ///
+    /// Man kann nur ein Channel-Abo haben. Es gilt das erste, das man abonniert hat. Dies wird nur
+    /// durch Verlassen des Servers oder servernotifyunregister zurückgesetzt.
+    /// Insbesondere wird es nicht zurückgesetzt, wenn der Channel gelöscht wird. Arrays als
+    /// Parameter sind nicht möglich. Beim Löschen eines Channels
+    /// geht das Abonnement nicht verloren.
+    pub async fn register_channel_events(&mut self) -> QueryResult<()> {
+        self.notify_register("servernotifyregister event=channel id=0\n\r")
+            .await
+    }
+
+    /// Subscribe channel events of `channel_id` only.
+    ///
+    /// Only one channel subscription is allowed per connection (see
+    /// [`Self::register_channel_events`]), the first one wins until `servernotifyunregister`.
+    #[allow(unused)]
+    pub async fn register_specific_channel_events(&mut self, channel_id: i64) -> QueryResult<()> {
+        self.notify_register(&format!(
+            "servernotifyregister event=channel id={channel_id}\n\r"
+        ))
+        .await
+    }
+
+    pub async fn change_nickname(&mut self, nickname: &str) -> QueryResult<()> {
+        self.basic_operation(&format!(
+            "clientupdate client_nickname={}\n\r",
+            Self::escape(nickname)
+        ))
+        .await?;
+        self.session.nickname = Some(nickname.to_string());
+        Ok(())
+    }
+
+    pub(crate) async fn client_get_database_id_from_uid(
+        &mut self,
+        uid: &str,
+    ) -> QueryResult<DatabaseId> {
+        self.query_operation_non_error(&format!("clientgetdbidfromuid cluid={uid}\n\r"))
+            .await
+            .map(|mut v| v.remove(0))
+    }
+
+    #[allow(unused)]
+    pub async fn client_get_uid_from_clid(&mut self, client_id: i64) -> QueryResult<String> {
+        self.query_one_operation::<ClientUid>(&format!("clientgetuidfromclid clid={client_id}\n\r"))
+            .await?
+            .map(|result| result.client_unique_id().to_string())
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    #[allow(unused)]
+    pub async fn client_get_name_from_dbid(&mut self, database_id: i64) -> QueryResult<String> {
+        self.query_one_operation::<ClientName>(&format!(
+            "clientgetnamefromdbid cldbid={database_id}\n\r"
+        ))
+        .await?
+        .map(|result| result.name().to_string())
+        .ok_or_else(QueryError::static_empty_response)
+    }
+
+    pub async fn query_client_database_info(
+        &mut self,
+        client_database_id: i64,
+    ) -> QueryResult<ClientDatabaseInfo> {
+        self.query_one_operation(&format!("clientdbinfo cldbid={client_database_id}\n\r"))
+            .await?
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    /// Page through client database, call again with `start + duration` until less than
+    /// `duration` entries are returned. `count` injected by `-count` is ignored.
+    #[allow(unused)]
+    pub async fn query_client_db_list(
+        &mut self,
+        start: u32,
+        duration: u32,
+    ) -> QueryResult<Vec<ClientDbEntry>> {
+        self.query_operation_or_empty(&format!(
+            "clientdblist start={start} duration={duration} -count\n\r"
+        ))
+        .await
+    }
+
+    /// Resolve `uid` to database id (cached for this connection) then query `clientdbinfo`.
+    pub async fn query_database_entry_by_uid(
+        &mut self,
+        uid: &str,
+    ) -> QueryResult<ClientDatabaseInfo> {
+        let client_database_id = match self.uid_cache.get(uid) {
+            Some(client_database_id) => *client_database_id,
+            None => {
+                let client_database_id = self
+                    .client_get_database_id_from_uid(uid)
+                    .await?
+                    .client_database_id();
+                self.uid_cache.insert(uid.to_string(), client_database_id);
+                client_database_id
+            }
+        };
+        self.query_client_database_info(client_database_id).await
+    }
+
+    /// Last known nickname of `uid`, cached for this connection.
+    #[allow(unused)]
+    pub async fn resolve_uid_to_display_name(&mut self, uid: &str) -> QueryResult<String> {
+        if let Some(name) = self.display_name_cache.get(uid) {
+            return Ok(name.clone());
+        }
+        let name = self
+            .query_database_entry_by_uid(uid)
+            .await?
+            .client_nickname()
+            .to_string();
+        self.display_name_cache
+            .insert(uid.to_string(), name.clone());
+        Ok(name)
+    }
+
+    pub async fn query_server_group_clients(
+        &mut self,
+        sgid: i64,
+    ) -> QueryResult<Vec<ServerGroupClient>> {
+        self.query_operation_or_empty(&format!("servergroupclientlist sgid={sgid}\n\r"))
+            .await
+    }
+
+    /// MyTeamSpeak id is only exposed by `clientinfo`, which needs client to be online, so offline
+    /// members of `sgid` are skipped.
+    #[allow(unused)]
+    pub async fn query_myts_ids_in_group(&mut self, sgid: i64) -> QueryResult<Vec<String>> {
+        let members = self
+            .query_server_group_clients(sgid)
+            .await?
+            .iter()
+            .map(|member| member.cldbid())
+            .collect::<Vec<_>>();
+        let mut ret = Vec::new();
+        for client in self.query_clients().await? {
+            if !members.contains(&client.client_database_id()) {
+                continue;
+            }
+            if let Some(id) = self
+                .query_client_info(client.client_id())
+                .await?
+                .as_ref()
+                .and_then(|info| info.client_myteamspeak_id())
+                && !ret.iter().any(|exist| exist == id)
+            {
+                ret.push(id.to_string());
+            }
+        }
+        Ok(ret)
+    }
+
+    pub async fn query_client_server_groups(
+        &mut self,
+        client_database_id: i64,
+    ) -> QueryResult<Vec<ServerGroupMember>> {
+        self.query_operation_or_empty(&format!(
+            "servergroupsbyclientid cldbid={client_database_id}\n\r"
+        ))
+        .await
+    }
+
+    /// `servergroupsbyclientid` has no switch to drop the names, so only `sgid` is deserialized.
+    #[allow(unused)]
+    pub async fn query_client_server_group_ids(
+        &mut self,
+        client_database_id: i64,
+    ) -> QueryResult<Vec<i64>> {
+        self.query_operation_or_empty::<ServerGroupId>(&format!(
+            "servergroupsbyclientid cldbid={client_database_id}\n\r"
+        ))
+        .await
+        .map(|groups| groups.iter().map(|group| group.sgid()).collect())
+    }
+
+    pub async fn query_client_channel_groups(
+        &mut self,
+        client_database_id: i64,
+    ) -> QueryResult<Vec<ChannelGroupMember>> {
+        self.query_operation_or_empty(&format!(
+            "channelgroupclientlist cldbid={client_database_id}\n\r"
+        ))
+        .await
+    }
+
+    /// `None` if client has no explicit channel group in `channel_id`, which means channel
+    /// default group is used.
+    #[allow(unused)]
+    pub async fn query_client_channel_group_in_channel(
+        &mut self,
+        client_database_id: i64,
+        channel_id: i64,
+    ) -> QueryResult<Option<i64>> {
+        let members: Vec<ChannelGroupMember> = self
+            .query_operation_or_empty(&format!(
+                "channelgroupclientlist cid={channel_id} cldbid={client_database_id}\n\r"
+            ))
+            .await?;
+        Ok(members.first().map(|member| member.cgid()))
+    }
+
+    #[allow(unused)]
+    pub async fn query_all_client_groups(
+        &mut self,
+        client_database_id: i64,
+    ) -> QueryResult<ClientGroupMembership> {
+        let server_groups = self.query_client_server_groups(client_database_id).await?;
+        let channel_groups = self.query_client_channel_groups(client_database_id).await?;
+        Ok(ClientGroupMembership::new(&server_groups, &channel_groups))
+    }
+
+    /// `command` is `banadd <rule>` or `banclient clid=<id>`, only first ban id is returned.
+    async fn create_ban(
+        &mut self,
+        command: &str,
+        duration_secs: Option<u64>,
+        reason: Option<&str>,
+    ) -> QueryResult<i64> {
+        let mut payload = command.to_string();
+        if let Some(duration) = duration_secs {
+            payload.push_str(&format!(" time={duration}"));
+        }
+        if let Some(reason) = reason {
+            payload.push_str(&format!(" banreason={}", Self::escape(reason)));
+        }
+        payload.push_str("\n\r");
+        self.query_one_operation::<CreatedBan>(&payload)
+            .await?
+            .map(|ban| ban.ban_id())
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    /// Ban online client, `time_seconds` of `None` means permanent. Returned ban id can be
+    /// passed to [`Self::ban_del`] to lift the ban.
+    #[allow(unused)]
+    pub async fn ban_client(
+        &mut self,
+        client_id: i64,
+        time_seconds: Option<u64>,
+        reason: &str,
+    ) -> QueryResult<i64> {
+        self.create_ban(
+            &format!("banclient clid={client_id}"),
+            time_seconds,
+            Some(reason).filter(|reason| !reason.is_empty()),
+        )
+        .await
+    }
+
+    /// Ban by client unique identifier, keep working when the client reconnect with a new IP.
+    /// `duration_secs` of `None` means permanent.
+    #[allow(unused)]
+    pub async fn ban_by_uid(
+        &mut self,
+        uid: &str,
+        duration_secs: Option<u64>,
+        reason: Option<&str>,
+    ) -> QueryResult<i64> {
+        self.create_ban(
+            &format!("banadd uid={}", Self::escape(uid)),
+            duration_secs,
+            reason,
+        )
+        .await
+    }
+
+    /// Ban by myTeamSpeak id.
+    #[allow(unused)]
+    pub async fn ban_by_mytsid(
+        &mut self,
+        mytsid: &str,
+        duration_secs: Option<u64>,
+        reason: Option<&str>,
+    ) -> QueryResult<i64> {
+        self.create_ban(
+            &format!("banadd mytsid={}", Self::escape(mytsid)),
+            duration_secs,
+            reason,
+        )
+        .await
+    }
+
+    /// Count entries of `complainlist` without deserializing them.
+    #[allow(unused)]
+    pub async fn count_complaints_against(&mut self, target_database_id: i64) -> QueryResult<u32> {
+        let data = self
+            .write_and_read(&format!("complainlist tcldbid={target_database_id}\n\r"))
+            .await?;
+        let content = match Self::decode_status(data) {
+            Ok(content) => content,
+            Err(e) if e.code() == 1281 => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        Ok(content
+            .lines()
+            .take_while(|line| !line.starts_with("error "))
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split('|').count() as u32)
+            .sum())
+    }
+
+    #[allow(unused)]
+    pub async fn ban_del(&mut self, ban_id: i64) -> QueryResult<()> {
         self.basic_operation(&format!("bandel banid={ban_id}\n\r"))
             .await
     }
+
+    /// Like [`Self::ban_del`] without reading response, status line is left to caller's read
+    /// loop, see [`Self::send_keepalive_unchecked`].
+    pub(crate) async fn ban_del_unchecked(&mut self, ban_id: i64) -> anyhow::Result<()> {
+        self.write_data(&format!("bandel banid={ban_id}\n\r")).await
+    }
+
+    #[allow(unused)]
+    pub async fn delete_all_bans(&mut self) -> QueryResult<()> {
+        self.basic_operation("bandelall\n\r").await
+    }
+
+    /// Server return error id 1281 instead of empty list if no ban exists.
+    pub async fn query_ban_list(&mut self) -> QueryResult<Vec<BanEntry>> {
+        self.query_operation_or_empty("banlist\n\r").await
+    }
+
+    /// Server side `banlist` can't filter by invoker, so filter is done locally.
+    #[allow(unused)]
+    pub async fn query_bans(
+        &mut self,
+        invoker_client_database_id: Option<i64>,
+    ) -> QueryResult<Vec<BanEntry>> {
+        let bans = self.query_ban_list().await?;
+        Ok(match invoker_client_database_id {
+            Some(invoker) => bans
+                .into_iter()
+                .filter(|entry| entry.invoker_client_database_id() == invoker)
+                .collect(),
+            None => bans,
+        })
+    }
+
     pub async fn query_client_info(&mut self, client_id: i64) -> QueryResult<Option<ClientInfo>> {
         self.query_one_operation(&format!("clientinfo clid={client_id}\n\r"))
             .await
     }
+
+    #[allow(unused)]
+    pub async fn query_client_connection_stats(
+        &mut self,
+        client_id: i64,
+    ) -> QueryResult<ConnectionStats> {
+        self.query_one_operation(&format!("clientinfo clid={client_id}\n\r"))
+            .await?
+            .ok_or_else(QueryError::static_empty_response)
+    }
+
+    #[allow(unused)]
+    pub async fn query_client_bandwidth(&mut self, client_id: i64) -> QueryResult<ClientBandwidth> {
+        self.query_one_operation(&format!("clientinfo clid={client_id}\n\r"))
+            .await?
+            .ok_or_else(QueryError::static_empty_response)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PingResult {
+    samples: Vec<Duration>,
+    mean: Duration,
+    /// In milliseconds
+    std_dev: f64,
+}
+
+impl PingResult {
+    fn from_samples(samples: Vec<Duration>) -> Self {
+        let count = samples.len().max(1) as f64;
+        let mean_ms = samples
+            .iter()
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .sum::<f64>()
+            / count;
+        let variance = samples
+            .iter()
+            .map(|d| (d.as_secs_f64() * 1000.0 - mean_ms).powi(2))
+            .sum::<f64>()
+            / count;
+        Self {
+            mean: Duration::from_secs_f64(mean_ms / 1000.0),
+            std_dev: variance.sqrt(),
+            samples,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+}
+
+/// Builder for `clientlist` with optional flags, results keep every returned field.
+#[derive(Clone, Debug, Default)]
+pub struct ClientListQuery {
+    flags: Vec<&'static str>,
+}
+
+impl ClientListQuery {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn flag(mut self, flag: &'static str) -> Self {
+        if !self.flags.contains(&flag) {
+            self.flags.push(flag);
+        }
+        self
+    }
+
+    #[allow(unused)]
+    pub fn with_uid(self) -> Self {
+        self.flag("-uid")
+    }
+    #[allow(unused)]
+    pub fn with_groups(self) -> Self {
+        self.flag("-groups")
+    }
+    #[allow(unused)]
+    pub fn with_away(self) -> Self {
+        self.flag("-away")
+    }
+    #[allow(unused)]
+    pub fn with_times(self) -> Self {
+        self.flag("-times")
+    }
+    #[allow(unused)]
+    pub fn with_voice(self) -> Self {
+        self.flag("-voice")
+    }
+    #[allow(unused)]
+    pub fn with_info(self) -> Self {
+        self.flag("-info")
+    }
+    #[allow(unused)]
+    pub fn with_country(self) -> Self {
+        self.flag("-country")
+    }
+    #[allow(unused)]
+    pub fn with_ip(self) -> Self {
+        self.flag("-ip")
+    }
+
+    fn payload(&self) -> String {
+        let mut payload = String::from("clientlist");
+        for flag in &self.flags {
+            payload.push(' ');
+            payload.push_str(flag);
+        }
+        payload.push_str("\n\r");
+        payload
+    }
+
+    #[allow(unused)]
+    pub async fn execute(&self, conn: &mut SocketConn) -> QueryResult<Vec<DynamicClient>> {
+        conn.query_operation_non_error(&self.payload()).await
+    }
+}
+
+/// Properties for `channeledit`, values are escaped when building payload.
+#[derive(Clone, Debug, Default)]
+pub struct ChannelProperties {
+    properties: Vec<(&'static str, String)>,
+}
+
+impl ChannelProperties {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn set(mut self, key: &'static str, value: String) -> Self {
+        match self.properties.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.properties.push((key, value)),
+        }
+        self
+    }
+
+    #[allow(unused)]
+    pub fn name(self, name: &str) -> Self {
+        self.set("channel_name", name.to_string())
+    }
+    #[allow(unused)]
+    pub fn topic(self, topic: &str) -> Self {
+        self.set("channel_topic", topic.to_string())
+    }
+    #[allow(unused)]
+    pub fn description(self, description: &str) -> Self {
+        self.set("channel_description", description.to_string())
+    }
+    #[allow(unused)]
+    pub fn password(self, password: &str) -> Self {
+        self.set("channel_password", password.to_string())
+    }
+    /// `None` means unlimited
+    #[allow(unused)]
+    pub fn max_clients(self, max: Option<i32>) -> Self {
+        self.set(
+            "channel_flag_maxclients_unlimited",
+            (max.is_none() as u8).to_string(),
+        )
+        .set("channel_maxclients", max.unwrap_or(-1).to_string())
+    }
+    #[allow(unused)]
+    pub fn permanent(self, permanent: bool) -> Self {
+        self.set("channel_flag_permanent", (permanent as u8).to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    fn payload(&self, channel_id: i64) -> String {
+        let mut payload = format!("channeledit cid={channel_id}");
+        for (key, value) in &self.properties {
+            payload.push_str(&format!(" {key}={}", SocketConn::escape(value)));
+        }
+        payload.push_str("\n\r");
+        payload
+    }
+}
+
+/// Compose BBCode for chat. Text of each fragment is escaped so it can't open or close tags,
+/// query escaping is left to the send function.
+#[derive(Clone, Debug, Default)]
+pub struct TextMessage {
+    content: String,
+}
+
+impl TextMessage {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Zero width space after `[` stops client from parsing it as a tag, displayed the same.
+    fn escape(text: &str) -> String {
+        text.replace('[', "[\u{200B}")
+    }
+
+    #[allow(unused)]
+    pub fn text(mut self, text: &str) -> Self {
+        self.content.push_str(&Self::escape(text));
+        self
+    }
+    #[allow(unused)]
+    pub fn bold(mut self, text: &str) -> Self {
+        self.content
+            .push_str(&format!("[b]{}[/b]", Self::escape(text)));
+        self
+    }
+    #[allow(unused)]
+    pub fn italic(mut self, text: &str) -> Self {
+        self.content
+            .push_str(&format!("[i]{}[/i]", Self::escape(text)));
+        self
+    }
+    /// `hex` is like `#ff0000`, leading `#` is optional.
+    #[allow(unused)]
+    pub fn color(mut self, hex: &str, text: &str) -> Self {
+        self.content.push_str(&format!(
+            "[color=#{}]{}[/color]",
+            hex.trim_start_matches('#'),
+            Self::escape(text)
+        ));
+        self
+    }
+    #[allow(unused)]
+    pub fn url(mut self, url: &str, label: &str) -> Self {
+        self.content.push_str(&format!(
+            "[URL={}]{}[/URL]",
+            url.replace('[', "%5B").replace(']', "%5D"),
+            Self::escape(label)
+        ));
+        self
+    }
+
+    pub fn build(self) -> String {
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        BUFFER_SIZE, ChannelProperties, ClientListQuery, PingResult, RateLimiter, SocketConn,
+        TextMessage,
+    };
+    use crate::types::{Channel, ServerGroup};
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::time::Instant;
+
+    /// Connect to a local server which send welcome message, then `chunks` with short delay
+    /// before each of them.
+    async fn connect_mock_server(chunks: Vec<Vec<u8>>) -> SocketConn {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream
+                .write_all(b"TS3\n\rWelcome to the TeamSpeak 3 ServerQuery interface\n\r")
+                .await
+                .unwrap();
+            for chunk in chunks {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                stream.write_all(&chunk).await.unwrap();
+            }
+            // Keep connection open until client finished reading
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        });
+        SocketConn::connect("127.0.0.1", addr.port()).await.unwrap()
+    }
+
+    #[test]
+    fn test_reconnect_replay_session() {
+        const OK: &[u8] = b"error id=0 msg=ok\n\r";
+        const WHOAMI: &[u8] =
+            b"client_id=7 client_database_id=3 client_nickname=bot\n\rerror id=0 msg=ok\n\r";
+        block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let server = tokio::spawn(async move {
+                let mut received = Vec::new();
+                // First connection is dropped after 4 commands
+                for commands in [4, 6] {
+                    let (stream, _) = listener.accept().await.unwrap();
+                    let mut stream = BufReader::new(stream);
+                    stream
+                        .get_mut()
+                        .write_all(b"TS3\n\rWelcome to the TeamSpeak 3 ServerQuery interface\n\r")
+                        .await
+                        .unwrap();
+                    for _ in 0..commands {
+                        let mut line = String::new();
+                        stream.read_line(&mut line).await.unwrap();
+                        let line = line.trim().to_string();
+                        let reply = if line == "whoami" { WHOAMI } else { OK };
+                        stream.get_mut().write_all(reply).await.unwrap();
+                        received.push(line);
+                    }
+                }
+                received
+            });
+
+            let mut conn = SocketConn::connect("127.0.0.1", port).await.unwrap();
+            conn.login("serveradmin", "secret").await.unwrap();
+            conn.select_server(1).await.unwrap();
+            conn.change_nickname("bot").await.unwrap();
+            conn.register_channel_events().await.unwrap();
+            assert!(!conn.connection_lost());
+
+            assert!(conn.who_am_i().await.is_err());
+            assert!(conn.connection_lost());
+
+            conn.reconnect().await.unwrap();
+            assert!(!conn.connection_lost());
+            assert_eq!(conn.cached_client_id(), Some(7));
+            conn.who_am_i().await.unwrap();
+
+            let received = server.await.unwrap();
+            // Notify registration is replayed after whoami
+            assert_eq!(received[..3], received[4..7]);
+            assert_eq!(received[7], "whoami");
+            assert_eq!(received[8], received[3]);
+            assert_eq!(received[9], "whoami");
+        });
+    }
+
+    #[test]
+    fn test_text_message() {
+        let message = TextMessage::new()
+            .bold("Hi")
+            .text(" [b]user[/b] ")
+            .color("#ff0000", "red")
+            .italic("it")
+            .url("https://example.com/a]b", "link")
+            .build();
+        assert_eq!(
+            message,
+            "[b]Hi[/b] [\u{200B}b]user[\u{200B}/b] [color=#ff0000]red[/color][i]it[/i][URL=https://example.com/a%5Db]link[/URL]"
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        block_on(async {
+            let mut limiter = RateLimiter::new(20.0);
+            let start = Instant::now();
+            for _ in 0..20 {
+                limiter.acquire().await;
+            }
+            assert!(start.elapsed() < Duration::from_millis(50));
+            for _ in 0..5 {
+                limiter.acquire().await;
+            }
+            assert!(start.elapsed() >= Duration::from_millis(240));
+        });
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_read_split_multibyte() {
+        // Put a 3-byte character across first read boundary, sent in one write so first read
+        // fill the whole buffer
+        let mut response = "a".repeat(BUFFER_SIZE - 1).into_bytes();
+        response.extend_from_slice("用户 Пользователь\n\rerror id=0 msg=ok\n\r".as_bytes());
+        let expected = String::from_utf8(response.clone()).unwrap();
+        let data = block_on(async {
+            connect_mock_server(vec![response])
+                .await
+                .read_data()
+                .await
+                .unwrap()
+        });
+        assert_eq!(data.as_deref(), Some(expected.as_str()));
+    }
+
+    #[test]
+    fn test_read_short_reads() {
+        let channels = (1..=30)
+            .map(|cid| format!("cid={cid} pid=0 channel_order=0 channel_name=Channel\\s{cid} total_clients=0 channel_needed_subscribe_power=0"))
+            .collect::<Vec<_>>()
+            .join("|");
+        let response = format!("{channels}\n\rerror id=0 msg=ok\n\r");
+        assert!(response.len() > BUFFER_SIZE * 2);
+        let chunks = response
+            .as_bytes()
+            .chunks(100)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        let data = block_on(async { connect_mock_server(chunks).await.read_data().await.unwrap() });
+        assert_eq!(data.as_deref(), Some(response.as_str()));
+        let channels = SocketConn::decode_status_with_result::<Channel>(data.unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(channels.len(), 30);
+    }
+
+    #[test]
+    fn test_channel_properties_payload() {
+        assert_eq!(
+            ChannelProperties::new()
+                .name("A | B")
+                .max_clients(Some(5))
+                .name("C")
+                .payload(3),
+            "channeledit cid=3 channel_name=C channel_flag_maxclients_unlimited=0 channel_maxclients=5\n\r"
+        );
+    }
+
+    #[test]
+    fn test_client_list_query_payload() {
+        assert_eq!(ClientListQuery::new().payload(), "clientlist\n\r");
+        assert_eq!(
+            ClientListQuery::new()
+                .with_uid()
+                .with_away()
+                .with_uid()
+                .payload(),
+            "clientlist -uid -away\n\r"
+        );
+    }
+
+    #[test]
+    fn test_ping_result() {
+        let result = PingResult::from_samples(vec![
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+            Duration::from_millis(30),
+        ]);
+        assert_eq!(result.mean(), Duration::from_millis(20));
+        assert!((result.std_dev() - 8.1649).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_decode_server_groups() {
+        let content = "sgid=1 name=Guest\\sServer\\sQuery type=2 iconid=0 savedb=0 sortid=0 namemode=0 n_modifyp=0 n_member_addp=0 n_member_removep=0|sgid=6 name=Server\\sAdmin type=1 iconid=300 savedb=1 sortid=0 namemode=0 n_modifyp=75 n_member_addp=75 n_member_removep=75\n\rerror id=0 msg=ok\n\r";
+        let groups = SocketConn::decode_status_with_result::<ServerGroup>(content.to_string())
+            .unwrap()
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].sgid(), 6);
+        assert_eq!(groups[1].name(), "Server Admin");
+    }
+
+    #[test]
+    fn test_first_response_end() {
+        let content = "cid=1 permid=133 permvalue=75 permnegated=0 permskip=0\n\rerror id=0 msg=ok\n\rerror id=1281 msg=database\\sempty\\sresult\\sset\n\r";
+        let end = SocketConn::first_response_end(content).unwrap();
+        assert!(content[..end].ends_with("error id=0 msg=ok\n\r"));
+        let rest = &content[end..];
+        assert_eq!(SocketConn::first_response_end(rest), Some(rest.len()));
+        assert_eq!(SocketConn::first_response_end("cid=1 permid=133"), None);
+    }
 }
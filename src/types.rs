@@ -3,11 +3,113 @@ pub trait FromQueryString: for<'de> Deserialize<'de> {
     where
         Self: Sized,
     {
-        serde_teamspeak_querystring::from_str(data)
+        serde_teamspeak_querystring::from_str(&escape::unescape_extended(data))
             .map_err(|e| anyhow::anyhow!("Got parser error: {e:?}"))
     }
 }
 
+pub mod escape {
+    use std::borrow::Cow;
+
+    /// (raw character, character following backslash when escaped) as ServerQuery manual defined
+    const ESCAPES: [(char, char); 11] = [
+        ('\\', '\\'),
+        ('/', '/'),
+        (' ', 's'),
+        ('|', 'p'),
+        ('\u{7}', 'a'),
+        ('\u{8}', 'b'),
+        ('\u{c}', 'f'),
+        ('\n', 'n'),
+        ('\r', 'r'),
+        ('\t', 't'),
+        ('\u{b}', 'v'),
+    ];
+
+    pub fn escape(s: &str) -> String {
+        let mut ret = String::with_capacity(s.len());
+        for c in s.chars() {
+            match ESCAPES.iter().find(|(raw, _)| *raw == c) {
+                Some((_, escaped)) => {
+                    ret.push('\\');
+                    ret.push(*escaped);
+                }
+                None => ret.push(c),
+            }
+        }
+        ret
+    }
+
+    /// Unknown escape sequence is kept as is.
+    #[allow(dead_code)]
+    pub fn unescape(s: &str) -> String {
+        let mut ret = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                ret.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some(next) => match ESCAPES.iter().find(|(_, escaped)| *escaped == next) {
+                    Some((raw, _)) => ret.push(*raw),
+                    None => {
+                        ret.push(c);
+                        ret.push(next);
+                    }
+                },
+                None => ret.push(c),
+            }
+        }
+        ret
+    }
+
+    /// Decode escapes which query string parser doesn't know (it drops them), `\\`, `\s` and
+    /// `\/` are kept for the parser. `data` must be a single entry already split by `|`.
+    pub(super) fn unescape_extended(data: &str) -> Cow<'_, str> {
+        if !data.contains('\\') {
+            return Cow::Borrowed(data);
+        }
+        let mut ret = String::with_capacity(data.len());
+        let mut chars = data.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                ret.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some(next @ ('\\' | 's' | '/')) => {
+                    ret.push(c);
+                    ret.push(next);
+                }
+                Some(next) => match ESCAPES.iter().find(|(_, escaped)| *escaped == next) {
+                    Some((raw, _)) => ret.push(*raw),
+                    None => {
+                        ret.push(c);
+                        ret.push(next);
+                    }
+                },
+                None => ret.push(c),
+            }
+        }
+        Cow::Owned(ret)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{escape, unescape};
+
+        #[test]
+        fn test_round_trip() {
+            let nickname = "a|b c\\d/e\nf\rg\th";
+            let escaped = escape(nickname);
+            assert_eq!(escaped, "a\\pb\\sc\\\\d\\/e\\nf\\rg\\th");
+            assert!(!escaped.contains(['|', ' ', '\n', '\r']));
+            assert_eq!(unescape(&escaped), nickname);
+        }
+    }
+}
+
 pub mod whoami {
     use super::FromQueryString;
     use serde::Deserialize;
@@ -30,6 +132,79 @@ pub mod whoami {
     impl FromQueryString for WhoAmI {}
 }
 
+pub mod self_info {
+    use super::{FromQueryString, WhoAmI};
+    use serde::{Deserialize, Deserializer};
+
+    fn from_comma_list<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.split(',')
+            .filter(|x| !x.is_empty())
+            .map(|x| x.parse().map_err(serde::de::Error::custom))
+            .collect()
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct SelfInfo {
+        #[serde(skip)]
+        client_id: i64,
+        client_database_id: i64,
+        client_unique_identifier: String,
+        #[serde(
+            rename = "client_servergroups",
+            deserialize_with = "from_comma_list",
+            default
+        )]
+        client_server_groups: Vec<i64>,
+    }
+
+    impl SelfInfo {
+        pub(crate) fn merge(mut self, who_am_i: &WhoAmI) -> Self {
+            self.client_id = who_am_i.client_id();
+            self.client_database_id = who_am_i.client_database_id();
+            self
+        }
+
+        #[allow(dead_code)]
+        pub fn client_id(&self) -> i64 {
+            self.client_id
+        }
+        #[allow(dead_code)]
+        pub fn client_database_id(&self) -> i64 {
+            self.client_database_id
+        }
+        #[allow(dead_code)]
+        pub fn client_unique_identifier(&self) -> &str {
+            &self.client_unique_identifier
+        }
+        #[allow(dead_code)]
+        pub fn client_server_groups(&self) -> &[i64] {
+            &self.client_server_groups
+        }
+    }
+
+    impl FromQueryString for SelfInfo {}
+
+    #[cfg(test)]
+    mod test {
+        use super::SelfInfo;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "cid=1 client_idle_time=0 client_unique_identifier=serveradmin client_nickname=auto\\schannel client_database_id=1 client_servergroups=2,8 client_type=1";
+
+        #[test]
+        fn test() {
+            let result = SelfInfo::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.client_database_id(), 1);
+            assert_eq!(result.client_unique_identifier(), "serveradmin");
+            assert_eq!(result.client_server_groups(), &[2, 8]);
+        }
+    }
+}
+
 pub mod create_channel {
     use super::FromQueryString;
     use serde::Deserialize;
@@ -48,6 +223,166 @@ pub mod create_channel {
     impl FromQueryString for CreateChannel {}
 }
 
+pub mod group {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ServerGroupType {
+        Template,
+        Regular,
+        Query,
+    }
+
+    impl ServerGroupType {
+        pub fn value(&self) -> i64 {
+            match self {
+                Self::Template => 0,
+                Self::Regular => 1,
+                Self::Query => 2,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct CreatedChannelGroup {
+        cgid: i64,
+    }
+
+    impl CreatedChannelGroup {
+        pub fn cgid(&self) -> i64 {
+            self.cgid
+        }
+    }
+
+    impl FromQueryString for CreatedChannelGroup {}
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ServerGroupMember {
+        sgid: i64,
+        #[serde(default)]
+        name: String,
+    }
+
+    impl ServerGroupMember {
+        pub fn sgid(&self) -> i64 {
+            self.sgid
+        }
+        #[allow(dead_code)]
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    impl FromQueryString for ServerGroupMember {}
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ServerGroup {
+        sgid: i64,
+        name: String,
+        #[serde(rename = "type")]
+        group_type: i64,
+        #[serde(rename = "sortid", default)]
+        sort_id: i64,
+    }
+
+    impl ServerGroup {
+        pub fn sgid(&self) -> i64 {
+            self.sgid
+        }
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+        #[allow(dead_code)]
+        pub fn group_type(&self) -> i64 {
+            self.group_type
+        }
+        pub fn sort_id(&self) -> i64 {
+            self.sort_id
+        }
+    }
+
+    impl FromQueryString for ServerGroup {}
+
+    #[derive(Clone, Copy, Debug, Default, Deserialize)]
+    pub struct ServerGroupId {
+        sgid: i64,
+    }
+
+    impl ServerGroupId {
+        pub fn sgid(&self) -> i64 {
+            self.sgid
+        }
+    }
+
+    impl FromQueryString for ServerGroupId {}
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelGroupMember {
+        cid: i64,
+        cldbid: i64,
+        cgid: i64,
+    }
+
+    impl ChannelGroupMember {
+        pub fn cid(&self) -> i64 {
+            self.cid
+        }
+        #[allow(dead_code)]
+        pub fn cldbid(&self) -> i64 {
+            self.cldbid
+        }
+        pub fn cgid(&self) -> i64 {
+            self.cgid
+        }
+    }
+
+    impl FromQueryString for ChannelGroupMember {}
+
+    /// `servergroupclientlist` entry
+    #[derive(Clone, Copy, Debug, Default, Deserialize)]
+    pub struct ServerGroupClient {
+        cldbid: i64,
+    }
+
+    impl ServerGroupClient {
+        pub fn cldbid(&self) -> i64 {
+            self.cldbid
+        }
+    }
+
+    impl FromQueryString for ServerGroupClient {}
+
+    #[derive(Clone, Debug, Default)]
+    pub struct ClientGroupMembership {
+        server_groups: Vec<i64>,
+        /// (channel id, channel group id)
+        channel_groups: Vec<(i64, i64)>,
+    }
+
+    impl ClientGroupMembership {
+        pub fn new(
+            server_groups: &[ServerGroupMember],
+            channel_groups: &[ChannelGroupMember],
+        ) -> Self {
+            Self {
+                server_groups: server_groups.iter().map(|g| g.sgid()).collect(),
+                channel_groups: channel_groups.iter().map(|g| (g.cid(), g.cgid())).collect(),
+            }
+        }
+
+        #[allow(dead_code)]
+        pub fn server_groups(&self) -> &[i64] {
+            &self.server_groups
+        }
+        #[allow(dead_code)]
+        pub fn channel_groups(&self) -> &[(i64, i64)] {
+            &self.channel_groups
+        }
+    }
+}
+
 pub mod channel {
     use std::hash::Hash;
 
@@ -79,6 +414,17 @@ pub mod channel {
         pub fn channel_name(&self) -> &str {
             &self.channel_name
         }
+        /// Spacer names look like `[spacer]`, `[cspacer0]title` or `[*spacer1]-`
+        pub fn is_spacer(&self) -> bool {
+            self.channel_name
+                .strip_prefix('[')
+                .and_then(|name| name.split_once(']'))
+                .is_some_and(|(tag, _)| {
+                    tag.strip_prefix(['c', 'l', 'r', '*'])
+                        .unwrap_or(tag)
+                        .starts_with("spacer")
+                })
+        }
         /*pub fn total_clients(&self) -> i64 {
             self.total_clients
         }
@@ -93,19 +439,618 @@ pub mod channel {
         fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
             self.channel_id.hash(state);
         }
-    }
-
-    impl PartialEq for Channel {
-        fn eq(&self, other: &Self) -> bool {
-            self.channel_id == other.channel_id
+    }
+
+    impl PartialEq for Channel {
+        fn eq(&self, other: &Self) -> bool {
+            self.channel_id == other.channel_id
+        }
+    }
+
+    impl Eq for Channel {}
+
+    impl PartialEq<i64> for Channel {
+        fn eq(&self, other: &i64) -> bool {
+            self.channel_id == *other
+        }
+    }
+
+    /// `channellist -voice` entry, serde flatten is not supported by the query string parser so
+    /// the [`Channel`] fields are repeated here.
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelWithVoice {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        channel_name: String,
+        #[serde(rename = "channel_codec")]
+        codec: i64,
+        #[serde(rename = "channel_codec_quality")]
+        codec_quality: i64,
+    }
+
+    impl ChannelWithVoice {
+        #[allow(dead_code)]
+        pub fn channel(&self) -> Channel {
+            Channel {
+                channel_id: self.channel_id,
+                channel_name: self.channel_name.clone(),
+            }
+        }
+        #[allow(dead_code)]
+        pub fn codec(&self) -> i64 {
+            self.codec
+        }
+        #[allow(dead_code)]
+        pub fn codec_quality(&self) -> i64 {
+            self.codec_quality
+        }
+    }
+
+    impl FromQueryString for ChannelWithVoice {}
+
+    #[derive(Deserialize)]
+    struct RawChannelWithTopic {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        channel_name: String,
+        #[serde(default)]
+        channel_topic: Option<String>,
+    }
+
+    /// `channellist -topic` entry
+    #[derive(Clone, Debug, Default, Deserialize)]
+    #[serde(from = "RawChannelWithTopic")]
+    pub struct ChannelWithTopic {
+        channel: Channel,
+        topic: String,
+    }
+
+    impl From<RawChannelWithTopic> for ChannelWithTopic {
+        fn from(raw: RawChannelWithTopic) -> Self {
+            Self {
+                channel: Channel {
+                    channel_id: raw.channel_id,
+                    channel_name: raw.channel_name,
+                },
+                topic: raw.channel_topic.unwrap_or_default(),
+            }
+        }
+    }
+
+    impl ChannelWithTopic {
+        #[allow(dead_code)]
+        pub fn channel(&self) -> &Channel {
+            &self.channel
+        }
+        #[allow(dead_code)]
+        pub fn topic(&self) -> &str {
+            &self.topic
+        }
+    }
+
+    impl FromQueryString for ChannelWithTopic {}
+
+    /// Channel with `channel_description` from `channelinfo`, `channellist` can't return it.
+    #[derive(Clone, Debug, Default)]
+    pub struct ChannelWithDescription {
+        channel: Channel,
+        description: String,
+    }
+
+    impl ChannelWithDescription {
+        pub fn new(channel: Channel, description: String) -> Self {
+            Self {
+                channel,
+                description,
+            }
+        }
+        #[allow(dead_code)]
+        pub fn channel(&self) -> &Channel {
+            &self.channel
+        }
+        #[allow(dead_code)]
+        pub fn description(&self) -> &str {
+            &self.description
+        }
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelWithLimits {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        channel_name: String,
+        total_clients: i64,
+        total_clients_family: i64,
+        #[serde(rename = "channel_maxclients")]
+        max_clients: i64,
+        #[serde(rename = "channel_maxfamilyclients")]
+        max_family_clients: i64,
+    }
+
+    impl ChannelWithLimits {
+        pub fn cid(&self) -> i64 {
+            self.channel_id
+        }
+        pub fn channel_name(&self) -> &str {
+            &self.channel_name
+        }
+        #[allow(dead_code)]
+        pub fn total_clients(&self) -> i64 {
+            self.total_clients
+        }
+        pub fn total_clients_family(&self) -> i64 {
+            self.total_clients_family
+        }
+        /// -1 means unlimited
+        #[allow(dead_code)]
+        pub fn max_clients(&self) -> i64 {
+            self.max_clients
+        }
+        /// -1 means unlimited
+        #[allow(dead_code)]
+        pub fn max_family_clients(&self) -> i64 {
+            self.max_family_clients
+        }
+
+        /// Keep counts up to date after a client moved into this channel.
+        pub fn client_moved_in(&mut self) {
+            self.total_clients += 1;
+            self.total_clients_family += 1;
+        }
+
+        pub fn has_room(&self) -> bool {
+            (self.max_clients < 0 || self.total_clients < self.max_clients)
+                && (self.max_family_clients < 0
+                    || self.total_clients_family < self.max_family_clients)
+        }
+    }
+
+    impl FromQueryString for ChannelWithLimits {}
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelWithFlags {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        channel_name: String,
+        #[serde(rename = "channel_flag_permanent")]
+        permanent: bool,
+        #[serde(rename = "channel_flag_semi_permanent")]
+        semi_permanent: bool,
+        #[serde(rename = "channel_flag_password")]
+        password: bool,
+        #[serde(rename = "channel_flag_default")]
+        default: bool,
+    }
+
+    impl ChannelWithFlags {
+        #[allow(dead_code)]
+        pub fn cid(&self) -> i64 {
+            self.channel_id
+        }
+        #[allow(dead_code)]
+        pub fn channel_name(&self) -> &str {
+            &self.channel_name
+        }
+        #[allow(dead_code)]
+        pub fn permanent(&self) -> bool {
+            self.permanent
+        }
+        #[allow(dead_code)]
+        pub fn semi_permanent(&self) -> bool {
+            self.semi_permanent
+        }
+        #[allow(dead_code)]
+        pub fn password(&self) -> bool {
+            self.password
+        }
+        #[allow(dead_code)]
+        pub fn is_default(&self) -> bool {
+            self.default
+        }
+
+        /// Temporary channels are deleted by server once empty
+        #[allow(dead_code)]
+        pub fn is_temporary(&self) -> bool {
+            !self.permanent && !self.semi_permanent
+        }
+    }
+
+    impl FromQueryString for ChannelWithFlags {}
+
+    #[cfg(test)]
+    mod test {
+        use super::{
+            Channel, ChannelWithFlags, ChannelWithLimits, ChannelWithTopic, ChannelWithVoice,
+        };
+        use crate::types::FromQueryString;
+
+        #[test]
+        fn test_with_topic() {
+            let result = ChannelWithTopic::from_query(
+                "cid=2 pid=0 channel_order=1 channel_name=Lobby total_clients=0 channel_needed_subscribe_power=0 channel_topic=Hello\\sworld",
+            )
+            .unwrap();
+            assert_eq!(result.channel().cid(), 2);
+            assert_eq!(result.topic(), "Hello world");
+        }
+
+        #[test]
+        fn test_with_limits() {
+            let result = ChannelWithLimits::from_query(
+                "cid=2 pid=0 channel_order=1 channel_name=Lobby total_clients=2 total_clients_family=3 channel_maxclients=2 channel_maxfamilyclients=-1",
+            )
+            .unwrap();
+            assert_eq!(result.cid(), 2);
+            assert!(!result.has_room());
+        }
+
+        #[test]
+        fn test_with_flags() {
+            let result = ChannelWithFlags::from_query(
+                "cid=2 pid=0 channel_order=1 channel_name=Lobby total_clients=0 channel_needed_subscribe_power=0 channel_flag_default=0 channel_flag_password=1 channel_flag_permanent=0 channel_flag_semi_permanent=1",
+            )
+            .unwrap();
+            assert!(result.password());
+            assert!(result.semi_permanent());
+            assert!(!result.is_temporary());
+        }
+
+        #[test]
+        fn test_with_voice() {
+            let result = ChannelWithVoice::from_query(
+                "cid=2 pid=0 channel_order=1 channel_name=Lobby total_clients=0 channel_needed_subscribe_power=0 channel_flag_default=0 channel_flag_password=0 channel_flag_permanent=1 channel_flag_semi_permanent=0 channel_codec=4 channel_codec_quality=6 channel_needed_talk_power=0",
+            )
+            .unwrap();
+            assert_eq!(result.channel().cid(), 2);
+            assert_eq!(result.channel().channel_name(), "Lobby");
+            assert_eq!(result.codec(), 4);
+            assert_eq!(result.codec_quality(), 6);
+        }
+
+        #[test]
+        fn test_is_spacer() {
+            let channel =
+                |name: &str| Channel::from_query(&format!("cid=1 channel_name={name}")).unwrap();
+            assert!(channel("[spacer0]").is_spacer());
+            assert!(channel("[cspacer]Lobby").is_spacer());
+            assert!(channel("[*spacer1]-").is_spacer());
+            assert!(!channel("Lobby").is_spacer());
+            assert!(!channel("[xspacer]").is_spacer());
+        }
+    }
+}
+
+pub mod channel_info {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelInfo {
+        pid: i64,
+        channel_name: String,
+        #[serde(rename = "channel_maxfamilyclients")]
+        channel_max_family_clients: i64,
+        #[serde(rename = "channel_flag_maxfamilyclients_unlimited")]
+        channel_flag_max_family_clients_unlimited: bool,
+        #[serde(rename = "channel_flag_maxfamilyclients_inherited")]
+        channel_flag_max_family_clients_inherited: bool,
+        #[serde(default)]
+        channel_description: Option<String>,
+        #[serde(default)]
+        channel_topic: Option<String>,
+        #[serde(default)]
+        channel_flag_password: bool,
+        #[serde(rename = "channel_maxclients", default)]
+        channel_max_clients: i64,
+        #[serde(rename = "channel_flag_maxclients_unlimited", default)]
+        channel_flag_max_clients_unlimited: bool,
+    }
+
+    impl ChannelInfo {
+        pub fn pid(&self) -> i64 {
+            self.pid
+        }
+        #[allow(dead_code)]
+        pub fn channel_name(&self) -> &str {
+            &self.channel_name
+        }
+        #[allow(dead_code)]
+        pub fn channel_max_family_clients(&self) -> i64 {
+            self.channel_max_family_clients
+        }
+        #[allow(dead_code)]
+        pub fn channel_flag_max_family_clients_unlimited(&self) -> bool {
+            self.channel_flag_max_family_clients_unlimited
+        }
+        pub fn channel_flag_max_family_clients_inherited(&self) -> bool {
+            self.channel_flag_max_family_clients_inherited
+        }
+        pub fn channel_description(&self) -> &str {
+            self.channel_description.as_deref().unwrap_or_default()
+        }
+        #[allow(dead_code)]
+        pub fn channel_topic(&self) -> &str {
+            self.channel_topic.as_deref().unwrap_or_default()
+        }
+        #[allow(dead_code)]
+        pub fn channel_flag_password(&self) -> bool {
+            self.channel_flag_password
+        }
+        #[allow(dead_code)]
+        pub fn channel_max_clients(&self) -> i64 {
+            self.channel_max_clients
+        }
+        #[allow(dead_code)]
+        pub fn channel_flag_max_clients_unlimited(&self) -> bool {
+            self.channel_flag_max_clients_unlimited
+        }
+
+        /// Client limit set on this channel
+        #[allow(dead_code)]
+        pub fn clients_limit(&self) -> Option<i64> {
+            (!self.channel_flag_max_clients_unlimited).then_some(self.channel_max_clients)
+        }
+
+        /// Family client limit set on this channel itself
+        pub fn family_clients_limit(&self) -> Option<i64> {
+            (!self.channel_flag_max_family_clients_unlimited
+                && !self.channel_flag_max_family_clients_inherited)
+                .then_some(self.channel_max_family_clients)
+        }
+    }
+
+    impl FromQueryString for ChannelInfo {}
+
+    /// Only `channel_name` of `channelinfo`
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelName {
+        channel_name: String,
+    }
+
+    impl ChannelName {
+        pub fn channel_name(&self) -> &str {
+            &self.channel_name
+        }
+    }
+
+    impl FromQueryString for ChannelName {}
+
+    /// `channelfind` entry
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ChannelMatch {
+        cid: i64,
+        channel_name: String,
+    }
+
+    impl ChannelMatch {
+        #[allow(dead_code)]
+        pub fn cid(&self) -> i64 {
+            self.cid
+        }
+        #[allow(dead_code)]
+        pub fn channel_name(&self) -> &str {
+            &self.channel_name
+        }
+    }
+
+    impl FromQueryString for ChannelMatch {}
+
+    #[cfg(test)]
+    mod test {
+        use super::ChannelInfo;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "pid=0 channel_name=Default\\sChannel channel_topic channel_description=Welcome channel_password channel_codec=4 channel_codec_quality=6 channel_maxclients=5 channel_maxfamilyclients=-1 channel_order=0 channel_flag_permanent=1 channel_flag_semi_permanent=0 channel_flag_default=1 channel_flag_password=1 channel_flag_maxclients_unlimited=0 channel_flag_maxfamilyclients_unlimited=1 channel_flag_maxfamilyclients_inherited=0";
+
+        #[test]
+        fn test() {
+            let result = ChannelInfo::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.channel_name(), "Default Channel");
+            assert_eq!(result.channel_topic(), "");
+            assert!(result.channel_flag_password());
+            assert_eq!(result.clients_limit(), Some(5));
+            assert_eq!(result.family_clients_limit(), None);
+        }
+    }
+}
+
+pub mod client_badges {
+    use super::FromQueryString;
+    use serde::{Deserialize, Deserializer};
+    use std::fmt::{Display, Formatter};
+
+    /// Badge GUID as sent by server. The server only knows GUIDs, names come from the badge
+    /// list the TeamSpeak client downloads, so no GUID to name table is kept here.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct BadgeId(String);
+
+    impl BadgeId {
+        #[allow(dead_code)]
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl Display for BadgeId {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    /// Value looks like `overwolf=0:badges=<guid>,<guid>`
+    fn from_badges<'de, D>(deserializer: D) -> Result<Vec<BadgeId>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<String>::deserialize(deserializer)?
+            .unwrap_or_default()
+            .split(':')
+            .find_map(|part| part.strip_prefix("badges="))
+            .map(|badges| {
+                badges
+                    .split(',')
+                    .filter(|badge| !badge.is_empty())
+                    .map(|badge| BadgeId(badge.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ClientWithBadges {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        #[serde(rename = "clid")]
+        client_id: i64,
+        client_database_id: i64,
+        client_type: i64,
+        client_nickname: String,
+        #[serde(rename = "client_badges", deserialize_with = "from_badges", default)]
+        badges: Vec<BadgeId>,
+    }
+
+    impl ClientWithBadges {
+        #[allow(dead_code)]
+        pub fn client_id(&self) -> i64 {
+            self.client_id
+        }
+        #[allow(dead_code)]
+        pub fn channel_id(&self) -> i64 {
+            self.channel_id
+        }
+        #[allow(dead_code)]
+        pub fn client_database_id(&self) -> i64 {
+            self.client_database_id
+        }
+        #[allow(dead_code)]
+        pub fn client_type(&self) -> i64 {
+            self.client_type
+        }
+        #[allow(dead_code)]
+        pub fn client_nickname(&self) -> &str {
+            &self.client_nickname
+        }
+        #[allow(dead_code)]
+        pub fn badges(&self) -> &[BadgeId] {
+            &self.badges
+        }
+    }
+
+    impl FromQueryString for ClientWithBadges {}
+
+    #[cfg(test)]
+    mod test {
+        use super::ClientWithBadges;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "clid=5 cid=1 client_database_id=3 client_nickname=user client_type=0 client_badges=overwolf=0:badges=c9e97536-5a2d-4c8e-a135-af404587a472,94ec66de-5940-4e38-b002-970df0cf6c94";
+
+        #[test]
+        fn test() {
+            let result = ClientWithBadges::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.badges().len(), 2);
+            assert_eq!(
+                result.badges()[0].as_str(),
+                "c9e97536-5a2d-4c8e-a135-af404587a472"
+            );
+            let result = ClientWithBadges::from_query(
+                "clid=5 cid=1 client_database_id=3 client_nickname=user client_type=0 client_badges",
+            )
+            .unwrap();
+            assert!(result.badges().is_empty());
+        }
+    }
+}
+
+pub mod client_ip {
+    use super::FromQueryString;
+    use serde::{Deserialize, Deserializer};
+    use std::net::IpAddr;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum ClientIp {
+        Parsed(IpAddr),
+        /// Kept as is if server return something `IpAddr` can't parse
+        Raw(String),
+    }
+
+    fn from_ip<'de, D>(deserializer: D) -> Result<Option<ClientIp>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<String>::deserialize(deserializer)?
+            .filter(|s| !s.is_empty())
+            .map(|s| match s.parse() {
+                Ok(ip) => ClientIp::Parsed(ip),
+                Err(_) => ClientIp::Raw(s),
+            }))
+    }
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ClientWithIp {
+        #[serde(rename = "cid")]
+        channel_id: i64,
+        #[serde(rename = "clid")]
+        client_id: i64,
+        client_database_id: i64,
+        client_type: i64,
+        client_nickname: String,
+        #[serde(rename = "connection_client_ip", deserialize_with = "from_ip", default)]
+        ip: Option<ClientIp>,
+    }
+
+    impl ClientWithIp {
+        #[allow(dead_code)]
+        pub fn client_id(&self) -> i64 {
+            self.client_id
+        }
+        #[allow(dead_code)]
+        pub fn channel_id(&self) -> i64 {
+            self.channel_id
+        }
+        #[allow(dead_code)]
+        pub fn client_database_id(&self) -> i64 {
+            self.client_database_id
+        }
+        #[allow(dead_code)]
+        pub fn client_type(&self) -> i64 {
+            self.client_type
+        }
+        #[allow(dead_code)]
+        pub fn client_nickname(&self) -> &str {
+            &self.client_nickname
+        }
+        #[allow(dead_code)]
+        pub fn ip(&self) -> Option<&ClientIp> {
+            self.ip.as_ref()
         }
     }
 
-    impl Eq for Channel {}
+    impl FromQueryString for ClientWithIp {}
 
-    impl PartialEq<i64> for Channel {
-        fn eq(&self, other: &i64) -> bool {
-            self.channel_id == *other
+    #[cfg(test)]
+    mod test {
+        use super::{ClientIp, ClientWithIp};
+        use crate::types::FromQueryString;
+
+        #[test]
+        fn test() {
+            let result = ClientWithIp::from_query(
+                "clid=5 cid=1 client_database_id=3 client_nickname=user client_type=0 connection_client_ip=2001:db8::1",
+            )
+            .unwrap();
+            assert_eq!(
+                result.ip(),
+                Some(&ClientIp::Parsed("2001:db8::1".parse().unwrap()))
+            );
+            let result = ClientWithIp::from_query(
+                "clid=5 cid=1 client_database_id=3 client_nickname=user client_type=0 connection_client_ip=[fe80::1%eth0]",
+            )
+            .unwrap();
+            assert_eq!(
+                result.ip(),
+                Some(&ClientIp::Raw("[fe80::1%eth0]".to_string()))
+            );
         }
     }
 }
@@ -167,6 +1112,134 @@ mod client {
             //assert_eq!(result.client_unique_identifier(), "serveradmin".to_string());
             //assert_eq!(result.client_database_id(), "1".to_string());
         }
+
+        #[test]
+        fn test_escaped_nickname() {
+            let result = Client::from_query(
+                "clid=8 cid=1 client_database_id=3 client_nickname=A\\s\\p\\sB\\\\p\\/\\t client_type=0",
+            )
+            .unwrap();
+            assert_eq!(result.client_nickname(), "A | B\\p/\t");
+        }
+    }
+}
+
+pub mod dynamic_client {
+    use super::FromQueryString;
+    use serde::{Deserialize, Deserializer};
+    use std::collections::HashMap;
+
+    fn from_optional_map<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(
+            HashMap::<String, Option<String>>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(k, v)| (k, v.unwrap_or_default()))
+                .collect(),
+        )
+    }
+
+    /// `clientlist` entry with whatever fields the requested flags returned.
+    #[derive(Clone, Debug, Default, Deserialize)]
+    #[serde(transparent)]
+    pub struct DynamicClient {
+        #[serde(deserialize_with = "from_optional_map")]
+        fields: HashMap<String, String>,
+    }
+
+    impl DynamicClient {
+        pub fn get(&self, key: &str) -> Option<&str> {
+            self.fields.get(key).map(|s| s.as_str())
+        }
+
+        fn get_parsed<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+            self.get(key).and_then(|s| s.parse().ok())
+        }
+
+        #[allow(dead_code)]
+        pub fn fields(&self) -> &HashMap<String, String> {
+            &self.fields
+        }
+        #[allow(dead_code)]
+        pub fn client_id(&self) -> Option<i64> {
+            self.get_parsed("clid")
+        }
+        #[allow(dead_code)]
+        pub fn channel_id(&self) -> Option<i64> {
+            self.get_parsed("cid")
+        }
+        #[allow(dead_code)]
+        pub fn client_database_id(&self) -> Option<i64> {
+            self.get_parsed("client_database_id")
+        }
+        #[allow(dead_code)]
+        pub fn client_type(&self) -> Option<i64> {
+            self.get_parsed("client_type")
+        }
+        #[allow(dead_code)]
+        pub fn client_nickname(&self) -> Option<&str> {
+            self.get("client_nickname")
+        }
+        /// Require `-uid`
+        #[allow(dead_code)]
+        pub fn client_unique_identifier(&self) -> Option<&str> {
+            self.get("client_unique_identifier")
+        }
+        /// Require `-groups`
+        #[allow(dead_code)]
+        pub fn client_server_groups(&self) -> Option<Vec<i64>> {
+            self.get("client_servergroups").map(|s| {
+                s.split(',')
+                    .filter_map(|group| group.parse().ok())
+                    .collect()
+            })
+        }
+        /// Require `-away`
+        #[allow(dead_code)]
+        pub fn client_away(&self) -> Option<bool> {
+            self.get("client_away").map(|s| s == "1")
+        }
+        /// Require `-times`
+        #[allow(dead_code)]
+        pub fn client_idle_time(&self) -> Option<i64> {
+            self.get_parsed("client_idle_time")
+        }
+        /// Require `-country`
+        #[allow(dead_code)]
+        pub fn client_country(&self) -> Option<&str> {
+            self.get("client_country")
+        }
+        /// Require `-ip`
+        #[allow(dead_code)]
+        pub fn connection_client_ip(&self) -> Option<&str> {
+            self.get("connection_client_ip")
+        }
+    }
+
+    impl FromQueryString for DynamicClient {}
+
+    #[cfg(test)]
+    mod test {
+        use super::DynamicClient;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "clid=8 cid=1 client_database_id=1 client_nickname=serveradmin\\sfrom\\s127.0.0.1:43042 client_type=1 client_away=0 client_away_message client_servergroups=2,8";
+
+        #[test]
+        fn test() {
+            let result = DynamicClient::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.client_id(), Some(8));
+            assert_eq!(
+                result.client_nickname(),
+                Some("serveradmin from 127.0.0.1:43042")
+            );
+            assert_eq!(result.client_away(), Some(false));
+            assert_eq!(result.get("client_away_message"), Some(""));
+            assert_eq!(result.client_server_groups(), Some(vec![2, 8]));
+            assert_eq!(result.client_country(), None);
+        }
     }
 }
 
@@ -351,10 +1424,171 @@ pub mod notifies {
         }
     }
 
+    /// `notifychanneldeleted`, also fired when temporary channel deleted by server after empty.
+    #[derive(Clone, Copy, Debug, Deserialize)]
+    pub struct ChannelDeletedEvent {
+        cid: i64,
+    }
+
+    impl ChannelDeletedEvent {
+        pub fn cid(&self) -> i64 {
+            self.cid
+        }
+    }
+
+    impl FromQueryString for ChannelDeletedEvent {}
     impl FromQueryString for NotifyClientMovedView {}
     impl FromQueryString for NotifyClientEnterView {}
     impl FromQueryString for NotifyClientLeftView {}
     impl FromQueryString for NotifyTextMessage {}
+
+    #[cfg(test)]
+    mod test {
+        use super::ChannelDeletedEvent;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str =
+            "notifychanneldeleted invokerid=0 invokername=Server invokeruid cid=12";
+
+        #[test]
+        fn test() {
+            let result = ChannelDeletedEvent::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.cid(), 12);
+        }
+    }
+}
+
+pub mod permission {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct Permission {
+        // permoverview use single letter keys
+        #[serde(rename = "permid", alias = "p")]
+        permission_id: u64,
+        #[serde(rename = "permvalue", alias = "v")]
+        permission_value: i64,
+        #[serde(rename = "permnegated", alias = "n", default)]
+        negated: bool,
+        #[serde(rename = "permskip", alias = "s", default)]
+        skip: bool,
+    }
+
+    impl Permission {
+        pub fn new(permission_id: u64, permission_value: i64) -> Self {
+            Self {
+                permission_id,
+                permission_value,
+                ..Default::default()
+            }
+        }
+
+        pub fn permission_id(&self) -> u64 {
+            self.permission_id
+        }
+        pub fn permission_value(&self) -> i64 {
+            self.permission_value
+        }
+        pub fn negated(&self) -> bool {
+            self.negated
+        }
+        pub fn skip(&self) -> bool {
+            self.skip
+        }
+    }
+
+    /// Newer server mix `group_id_end=` entries into `permissionlist`, so every field is optional.
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct PermissionDefinition {
+        #[serde(rename = "permid", default)]
+        permission_id: u64,
+        #[serde(rename = "permname", default)]
+        permission_name: String,
+        #[serde(rename = "permdesc", default)]
+        permission_description: String,
+    }
+
+    impl PermissionDefinition {
+        pub fn permission_id(&self) -> u64 {
+            self.permission_id
+        }
+        pub fn permission_name(&self) -> &str {
+            &self.permission_name
+        }
+        #[allow(dead_code)]
+        pub fn permission_description(&self) -> &str {
+            &self.permission_description
+        }
+        pub fn is_group_marker(&self) -> bool {
+            self.permission_name.is_empty()
+        }
+    }
+
+    impl FromQueryString for PermissionDefinition {}
+
+    impl From<(u64, i64)> for Permission {
+        fn from((permission_id, permission_value): (u64, i64)) -> Self {
+            Self::new(permission_id, permission_value)
+        }
+    }
+
+    impl FromQueryString for Permission {}
+
+    #[derive(Clone, Debug, Default)]
+    pub struct NamedPermission {
+        name: String,
+        value: i64,
+        negated: bool,
+        skip: bool,
+    }
+
+    impl NamedPermission {
+        pub fn new(permission: &Permission, name: String) -> Self {
+            Self {
+                name,
+                value: permission.permission_value,
+                negated: permission.negated,
+                skip: permission.skip,
+            }
+        }
+
+        #[allow(dead_code)]
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+        #[allow(dead_code)]
+        pub fn value(&self) -> i64 {
+            self.value
+        }
+        #[allow(dead_code)]
+        pub fn negated(&self) -> bool {
+            self.negated
+        }
+        #[allow(dead_code)]
+        pub fn skip(&self) -> bool {
+            self.skip
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::Permission;
+        use crate::types::FromQueryString;
+
+        #[test]
+        fn test() {
+            let result =
+                Permission::from_query("cid=2 permid=133 permvalue=75 permnegated=0 permskip=0")
+                    .unwrap();
+            assert_eq!(result.permission_id(), 133);
+            assert_eq!(result.permission_value(), 75);
+            let result = Permission::from_query("t=0 id=0 id2=0 p=133 v=75 n=1 s=0").unwrap();
+            assert_eq!(result.permission_id(), 133);
+            assert_eq!(result.permission_value(), 75);
+            assert!(result.negated());
+        }
+    }
 }
 
 pub mod query_status {
@@ -373,6 +1607,7 @@ pub mod query_status {
             QueryStatus {
                 id: self.code,
                 msg: self.message,
+                extra_msg: None,
             }
         }
     }
@@ -387,6 +1622,8 @@ pub mod query_status {
     pub struct QueryStatus {
         id: i32,
         msg: String,
+        #[serde(default)]
+        extra_msg: Option<String>,
     }
 
     impl Default for QueryStatus {
@@ -394,60 +1631,631 @@ pub mod query_status {
             Self {
                 id: 0,
                 msg: "ok".to_string(),
+                extra_msg: None,
+            }
+        }
+    }
+
+    impl QueryStatus {
+        pub fn id(&self) -> i32 {
+            self.id
+        }
+        pub fn msg(&self) -> &String {
+            &self.msg
+        }
+        pub fn extra_msg(&self) -> Option<&str> {
+            self.extra_msg.as_deref()
+        }
+
+        pub fn into_err(self) -> QueryError {
+            QueryError::from(self)
+        }
+
+        pub fn into_result<T>(self, ret: T) -> QueryResult<T> {
+            if self.id == 0 {
+                return Ok(ret);
+            }
+            Err(self.into_err())
+        }
+    }
+
+    impl TryFrom<&str> for QueryStatus {
+        type Error = anyhow::Error;
+
+        fn try_from(value: &str) -> Result<Self, Self::Error> {
+            let (_, line) = value
+                .split_once("error ")
+                .ok_or_else(|| anyhow!("Split error: {}", value))?;
+            serde_teamspeak_querystring::from_str(line)
+                .map_err(|e| anyhow!("Got error while parse string: {:?} {:?}", line, e))
+        }
+    }
+}
+
+pub mod server_info {
+    use super::FromQueryString;
+    use serde::Deserialize;
+    use std::fmt::{Display, Formatter};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Clone, Debug, Deserialize)]
+    pub struct ServerInfo {
+        #[serde(rename = "virtualserver_unique_identifier")]
+        virtual_server_unique_identifier: String,
+        /// MiB per month
+        #[serde(rename = "virtualserver_download_quota", default)]
+        download_quota: u64,
+        /// MiB per month
+        #[serde(rename = "virtualserver_upload_quota", default)]
+        upload_quota: u64,
+        #[serde(rename = "virtualserver_month_bytes_downloaded", default)]
+        month_bytes_downloaded: u64,
+        #[serde(rename = "virtualserver_month_bytes_uploaded", default)]
+        month_bytes_uploaded: u64,
+        #[serde(rename = "virtualserver_channelsonline", default)]
+        channels_online: u32,
+        /// Including query clients
+        #[serde(rename = "virtualserver_clientsonline", default)]
+        clients_online: u32,
+        #[serde(rename = "virtualserver_queryclientsonline", default)]
+        query_clients_online: u32,
+        /// Unix timestamp
+        #[serde(rename = "virtualserver_created", default)]
+        virtual_server_created: u64,
+    }
+
+    impl ServerInfo {
+        pub fn virtual_server_unique_identifier(&self) -> &str {
+            &self.virtual_server_unique_identifier
+        }
+        #[allow(dead_code)]
+        pub fn virtual_server_created(&self) -> u64 {
+            self.virtual_server_created
+        }
+        /// Time elapsed since virtual server was created, `None` if clock is behind server's
+        #[allow(dead_code)]
+        pub fn created_elapsed(&self) -> Option<Duration> {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH + Duration::from_secs(self.virtual_server_created))
+                .ok()
+        }
+        pub fn channels_online(&self) -> u32 {
+            self.channels_online
+        }
+        pub fn clients_online(&self) -> u32 {
+            self.clients_online
+        }
+        pub fn query_clients_online(&self) -> u32 {
+            self.query_clients_online
+        }
+        pub fn quota_info(&self) -> QuotaInfo {
+            QuotaInfo {
+                upload_used: ByteSize(self.month_bytes_uploaded),
+                download_used: ByteSize(self.month_bytes_downloaded),
+            }
+        }
+        pub fn transfer_quotas(&self) -> TransferQuotas {
+            // Server reports totals in MiB, `u64::MAX` stays as unlimited
+            let to_bytes = |mib: u64| {
+                if mib == u64::MAX {
+                    u64::MAX
+                } else {
+                    mib.saturating_mul(1024 * 1024)
+                }
+            };
+            TransferQuotas {
+                download_quota_used: self.month_bytes_downloaded,
+                download_quota_total: to_bytes(self.download_quota),
+                upload_quota_used: self.month_bytes_uploaded,
+                upload_quota_total: to_bytes(self.upload_quota),
+            }
+        }
+    }
+
+    /// All values are bytes, used values are of this month, `u64::MAX` total means unlimited.
+    #[allow(dead_code)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct TransferQuotas {
+        pub download_quota_used: u64,
+        pub download_quota_total: u64,
+        pub upload_quota_used: u64,
+        pub upload_quota_total: u64,
+    }
+
+    impl FromQueryString for ServerInfo {}
+
+    #[allow(dead_code)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum HostMessageMode {
+        None,
+        /// Show in chat log
+        Log,
+        /// Show in a modal dialog
+        Modal,
+        /// Show in a modal dialog then disconnect
+        ModalQuit,
+    }
+
+    impl HostMessageMode {
+        pub fn value(&self) -> i64 {
+            match self {
+                Self::None => 0,
+                Self::Log => 1,
+                Self::Modal => 2,
+                Self::ModalQuit => 3,
+            }
+        }
+    }
+    /// Bytes, displayed with binary unit
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct ByteSize(pub u64);
+
+    impl Display for ByteSize {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+            if self.0 < 1024 {
+                return write!(f, "{} B", self.0);
             }
+            let mut value = self.0 as f64 / 1024.0;
+            let mut unit = 0;
+            while value >= 1024.0 && unit < UNITS.len() - 1 {
+                value /= 1024.0;
+                unit += 1;
+            }
+            write!(f, "{value:.2} {}", UNITS[unit])
+        }
+    }
+
+    /// Transfer used in this month
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct QuotaInfo {
+        pub upload_used: ByteSize,
+        pub download_used: ByteSize,
+    }
+
+    impl QuotaInfo {
+        pub fn emit(&self) {
+            metrics::gauge!("server_upload_quota_used_bytes").set(self.upload_used.0 as f64);
+            metrics::gauge!("server_download_quota_used_bytes").set(self.download_used.0 as f64);
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{ByteSize, ServerInfo};
+        use crate::types::FromQueryString;
+
+        #[test]
+        fn test_transfer_quotas_in_bytes() {
+            let info = ServerInfo::from_query(
+                "virtualserver_unique_identifier=abcd virtualserver_download_quota=18446744073709551615 virtualserver_upload_quota=2 virtualserver_month_bytes_downloaded=1048576 virtualserver_month_bytes_uploaded=1048576",
+            )
+            .unwrap();
+            let quotas = info.transfer_quotas();
+            assert_eq!(quotas.download_quota_total, u64::MAX);
+            assert_eq!(quotas.download_quota_used, 1024 * 1024);
+            assert_eq!(quotas.upload_quota_total, 2 * 1024 * 1024);
+            // Half of the 2 MiB quota used
+            assert_eq!(quotas.upload_quota_total / quotas.upload_quota_used, 2);
+        }
+
+        #[test]
+        fn test_client_counts() {
+            let info = ServerInfo::from_query(
+                "virtualserver_unique_identifier=abcd virtualserver_clientsonline=5 virtualserver_queryclientsonline=2 virtualserver_channelsonline=4",
+            )
+            .unwrap();
+            assert_eq!(info.clients_online(), 5);
+            assert_eq!(info.query_clients_online(), 2);
+            assert_eq!(info.channels_online(), 4);
+        }
+
+        #[test]
+        fn test_byte_size() {
+            assert_eq!(ByteSize(512).to_string(), "512 B");
+            assert_eq!(ByteSize(1536).to_string(), "1.50 KiB");
+            assert_eq!(ByteSize(5 * 1024 * 1024 * 1024).to_string(), "5.00 GiB");
+        }
+    }
+}
+
+pub mod virtual_server {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    /// `serverlist -uid` entry
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct VirtualServerWithStats {
+        #[serde(rename = "virtualserver_id")]
+        server_id: i64,
+        #[serde(rename = "virtualserver_port", default)]
+        port: u16,
+        #[serde(rename = "virtualserver_status")]
+        status: String,
+        #[serde(rename = "virtualserver_name", default)]
+        name: String,
+        #[serde(rename = "virtualserver_unique_identifier", default)]
+        unique_identifier: String,
+        /// Following fields are only present when server is online
+        #[serde(rename = "virtualserver_clientsonline", default)]
+        clients_online: i64,
+        #[serde(rename = "virtualserver_queryclientsonline", default)]
+        query_clients_online: i64,
+        #[serde(rename = "virtualserver_maxclients", default)]
+        max_clients: i64,
+        /// In seconds
+        #[serde(rename = "virtualserver_uptime", default)]
+        uptime: i64,
+    }
+
+    impl VirtualServerWithStats {
+        #[allow(dead_code)]
+        pub fn server_id(&self) -> i64 {
+            self.server_id
+        }
+        #[allow(dead_code)]
+        pub fn port(&self) -> u16 {
+            self.port
+        }
+        #[allow(dead_code)]
+        pub fn status(&self) -> &str {
+            &self.status
+        }
+        #[allow(dead_code)]
+        pub fn is_online(&self) -> bool {
+            self.status == "online"
+        }
+        #[allow(dead_code)]
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+        #[allow(dead_code)]
+        pub fn unique_identifier(&self) -> &str {
+            &self.unique_identifier
+        }
+        #[allow(dead_code)]
+        pub fn clients_online(&self) -> i64 {
+            self.clients_online
+        }
+        #[allow(dead_code)]
+        pub fn query_clients_online(&self) -> i64 {
+            self.query_clients_online
+        }
+        #[allow(dead_code)]
+        pub fn max_clients(&self) -> i64 {
+            self.max_clients
+        }
+        #[allow(dead_code)]
+        pub fn uptime(&self) -> i64 {
+            self.uptime
+        }
+    }
+
+    impl FromQueryString for VirtualServerWithStats {}
+
+    #[cfg(test)]
+    mod test {
+        use super::VirtualServerWithStats;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "virtualserver_id=1 virtualserver_port=9987 virtualserver_status=online virtualserver_clientsonline=3 virtualserver_queryclientsonline=2 virtualserver_maxclients=32 virtualserver_uptime=3600 virtualserver_name=TeamSpeak\\s]I[\\sServer virtualserver_autostart=1 virtualserver_machine_id virtualserver_unique_identifier=abcd";
+
+        #[test]
+        fn test() {
+            let result = VirtualServerWithStats::from_query(TEST_STRING).unwrap();
+            assert!(result.is_online());
+            assert_eq!(result.name(), "TeamSpeak ]I[ Server");
+            assert_eq!(result.clients_online(), 3);
+            assert_eq!(result.uptime(), 3600);
         }
     }
+}
+
+pub mod host_info {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize)]
+    pub struct HostInfo {
+        instance_uptime: i64,
+        host_timestamp_utc: i64,
+        #[serde(rename = "virtualservers_running_total", default)]
+        virtual_servers_running_total: i64,
+    }
 
-    impl QueryStatus {
-        pub fn id(&self) -> i32 {
-            self.id
+    impl HostInfo {
+        #[allow(dead_code)]
+        pub fn instance_uptime(&self) -> i64 {
+            self.instance_uptime
         }
-        pub fn msg(&self) -> &String {
-            &self.msg
+        pub fn host_timestamp_utc(&self) -> i64 {
+            self.host_timestamp_utc
         }
+        #[allow(dead_code)]
+        pub fn virtual_servers_running_total(&self) -> i64 {
+            self.virtual_servers_running_total
+        }
+    }
 
-        pub fn into_err(self) -> QueryError {
-            QueryError::from(self)
+    impl FromQueryString for HostInfo {}
+}
+
+pub mod instance_settings {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct InstanceSettings {
+        #[serde(rename = "serverinstance_database_version", default)]
+        database_version: i64,
+        #[serde(rename = "serverinstance_filetransfer_port")]
+        filetransfer_port: u16,
+        #[serde(rename = "serverinstance_max_download_total_bandwidth", default)]
+        max_download_total_bandwidth: u64,
+        #[serde(rename = "serverinstance_max_upload_total_bandwidth", default)]
+        max_upload_total_bandwidth: u64,
+        #[serde(rename = "serverinstance_guest_serverquery_group")]
+        guest_serverquery_group: i64,
+        #[serde(rename = "serverinstance_serverquery_flood_commands")]
+        serverquery_flood_commands: i64,
+        #[serde(rename = "serverinstance_serverquery_flood_time")]
+        serverquery_flood_time: i64,
+        #[serde(rename = "serverinstance_serverquery_ban_time")]
+        serverquery_ban_time: i64,
+        #[serde(rename = "serverinstance_template_serveradmin_group")]
+        template_serveradmin_group: i64,
+        #[serde(rename = "serverinstance_template_serverdefault_group")]
+        template_serverdefault_group: i64,
+        #[serde(rename = "serverinstance_template_channeladmin_group")]
+        template_channeladmin_group: i64,
+        #[serde(rename = "serverinstance_template_channeldefault_group")]
+        template_channeldefault_group: i64,
+        #[serde(rename = "serverinstance_pending_connections_per_ip", default)]
+        pending_connections_per_ip: i64,
+    }
+
+    impl InstanceSettings {
+        #[allow(dead_code)]
+        pub fn database_version(&self) -> i64 {
+            self.database_version
+        }
+        #[allow(dead_code)]
+        pub fn filetransfer_port(&self) -> u16 {
+            self.filetransfer_port
+        }
+        /// Bytes per second, `18446744073709551615` means unlimited
+        #[allow(dead_code)]
+        pub fn max_download_total_bandwidth(&self) -> u64 {
+            self.max_download_total_bandwidth
+        }
+        #[allow(dead_code)]
+        pub fn max_upload_total_bandwidth(&self) -> u64 {
+            self.max_upload_total_bandwidth
+        }
+        #[allow(dead_code)]
+        pub fn guest_serverquery_group(&self) -> i64 {
+            self.guest_serverquery_group
+        }
+        #[allow(dead_code)]
+        pub fn serverquery_flood_commands(&self) -> i64 {
+            self.serverquery_flood_commands
+        }
+        #[allow(dead_code)]
+        pub fn serverquery_flood_time(&self) -> i64 {
+            self.serverquery_flood_time
+        }
+        #[allow(dead_code)]
+        pub fn serverquery_ban_time(&self) -> i64 {
+            self.serverquery_ban_time
+        }
+        #[allow(dead_code)]
+        pub fn template_serveradmin_group(&self) -> i64 {
+            self.template_serveradmin_group
+        }
+        #[allow(dead_code)]
+        pub fn template_serverdefault_group(&self) -> i64 {
+            self.template_serverdefault_group
+        }
+        #[allow(dead_code)]
+        pub fn template_channeladmin_group(&self) -> i64 {
+            self.template_channeladmin_group
+        }
+        #[allow(dead_code)]
+        pub fn template_channeldefault_group(&self) -> i64 {
+            self.template_channeldefault_group
+        }
+        #[allow(dead_code)]
+        pub fn pending_connections_per_ip(&self) -> i64 {
+            self.pending_connections_per_ip
+        }
+    }
+
+    impl FromQueryString for InstanceSettings {}
+
+    /// Instance default groups, the server names them `serverinstance_template_*_group` and
+    /// `serverinstance_guest_serverquery_group`.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct DefaultGroups {
+        serveradmin_group: i64,
+        serverdefault_group: i64,
+        serverquery_group: i64,
+        channeladmin_group: i64,
+        channeldefault_group: i64,
+    }
+
+    impl DefaultGroups {
+        #[allow(dead_code)]
+        pub fn serveradmin_group(&self) -> i64 {
+            self.serveradmin_group
+        }
+        #[allow(dead_code)]
+        pub fn serverdefault_group(&self) -> i64 {
+            self.serverdefault_group
+        }
+        #[allow(dead_code)]
+        pub fn serverquery_group(&self) -> i64 {
+            self.serverquery_group
+        }
+        #[allow(dead_code)]
+        pub fn channeladmin_group(&self) -> i64 {
+            self.channeladmin_group
         }
+        #[allow(dead_code)]
+        pub fn channeldefault_group(&self) -> i64 {
+            self.channeldefault_group
+        }
+    }
 
-        pub fn into_result<T>(self, ret: T) -> QueryResult<T> {
-            if self.id == 0 {
-                return Ok(ret);
+    impl From<&InstanceSettings> for DefaultGroups {
+        fn from(settings: &InstanceSettings) -> Self {
+            Self {
+                serveradmin_group: settings.template_serveradmin_group,
+                serverdefault_group: settings.template_serverdefault_group,
+                serverquery_group: settings.guest_serverquery_group,
+                channeladmin_group: settings.template_channeladmin_group,
+                channeldefault_group: settings.template_channeldefault_group,
             }
-            Err(self.into_err())
         }
     }
 
-    impl TryFrom<&str> for QueryStatus {
-        type Error = anyhow::Error;
+    #[cfg(test)]
+    mod test {
+        use super::{DefaultGroups, InstanceSettings};
+        use crate::types::FromQueryString;
 
-        fn try_from(value: &str) -> Result<Self, Self::Error> {
-            let (_, line) = value
-                .split_once("error ")
-                .ok_or_else(|| anyhow!("Split error: {}", value))?;
-            serde_teamspeak_querystring::from_str(line)
-                .map_err(|e| anyhow!("Got error while parse string: {:?} {:?}", line, e))
+        const TEST_STRING: &str = "serverinstance_database_version=26 serverinstance_filetransfer_port=30033 serverinstance_max_download_total_bandwidth=18446744073709551615 serverinstance_max_upload_total_bandwidth=18446744073709551615 serverinstance_guest_serverquery_group=1 serverinstance_serverquery_flood_commands=50 serverinstance_serverquery_flood_time=3 serverinstance_serverquery_ban_time=600 serverinstance_template_serveradmin_group=3 serverinstance_template_serverdefault_group=5 serverinstance_template_channeladmin_group=1 serverinstance_template_channeldefault_group=4 serverinstance_permissions_version=19 serverinstance_pending_connections_per_ip=0";
+
+        #[test]
+        fn test() {
+            let result = InstanceSettings::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.filetransfer_port(), 30033);
+            assert_eq!(result.max_download_total_bandwidth(), u64::MAX);
+            assert_eq!(result.serverquery_flood_commands(), 50);
+            assert_eq!(result.template_serveradmin_group(), 3);
+            let groups = DefaultGroups::from(&result);
+            assert_eq!(groups.serverquery_group(), 1);
+            assert_eq!(groups.channeldefault_group(), 4);
         }
     }
 }
 
-pub mod server_info {
+pub mod client_database_info {
     use super::FromQueryString;
     use serde::Deserialize;
 
-    #[derive(Clone, Debug, Deserialize)]
-    pub struct ServerInfo {
-        #[serde(rename = "virtualserver_unique_identifier")]
-        virtual_server_unique_identifier: String,
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ClientDatabaseInfo {
+        client_unique_identifier: String,
+        client_nickname: String,
+        client_database_id: i64,
+        client_created: i64,
+        #[serde(rename = "client_lastconnected")]
+        client_last_connected: i64,
+        #[serde(rename = "client_totalconnections")]
+        client_total_connections: i64,
+        #[serde(default)]
+        client_description: Option<String>,
+        #[serde(rename = "client_lastip", default)]
+        client_last_ip: Option<String>,
     }
 
-    impl ServerInfo {
-        pub fn virtual_server_unique_identifier(&self) -> &str {
-            &self.virtual_server_unique_identifier
+    impl ClientDatabaseInfo {
+        #[allow(dead_code)]
+        pub fn client_unique_identifier(&self) -> &str {
+            &self.client_unique_identifier
+        }
+        pub fn client_nickname(&self) -> &str {
+            &self.client_nickname
+        }
+        #[allow(dead_code)]
+        pub fn client_database_id(&self) -> i64 {
+            self.client_database_id
+        }
+        #[allow(dead_code)]
+        pub fn client_created(&self) -> i64 {
+            self.client_created
+        }
+        #[allow(dead_code)]
+        pub fn client_last_connected(&self) -> i64 {
+            self.client_last_connected
+        }
+        #[allow(dead_code)]
+        pub fn client_total_connections(&self) -> i64 {
+            self.client_total_connections
+        }
+        #[allow(dead_code)]
+        pub fn client_description(&self) -> Option<&str> {
+            self.client_description.as_deref()
+        }
+        #[allow(dead_code)]
+        pub fn client_last_ip(&self) -> Option<&str> {
+            self.client_last_ip.as_deref()
         }
     }
 
-    impl FromQueryString for ServerInfo {}
+    impl FromQueryString for ClientDatabaseInfo {}
+
+    /// `clientdblist` entry, first entry also carries `count` if `-count` is used
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ClientDbEntry {
+        cldbid: i64,
+        client_unique_identifier: String,
+        client_nickname: String,
+        #[serde(rename = "client_lastconnected")]
+        client_last_connected: i64,
+    }
+
+    impl ClientDbEntry {
+        #[allow(dead_code)]
+        pub fn cldbid(&self) -> i64 {
+            self.cldbid
+        }
+        #[allow(dead_code)]
+        pub fn client_unique_identifier(&self) -> &str {
+            &self.client_unique_identifier
+        }
+        #[allow(dead_code)]
+        pub fn client_nickname(&self) -> &str {
+            &self.client_nickname
+        }
+        #[allow(dead_code)]
+        pub fn client_last_connected(&self) -> i64 {
+            self.client_last_connected
+        }
+    }
+
+    impl FromQueryString for ClientDbEntry {}
+
+    #[cfg(test)]
+    mod test {
+        use super::{ClientDatabaseInfo, ClientDbEntry};
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "client_unique_identifier=Nbzbqqq6e1Ux1+3S\\/C4a0Qz9Q4k= client_nickname=user client_database_id=3 client_created=1700000000 client_lastconnected=1700001000 client_totalconnections=12 client_flag_avatar client_description client_month_bytes_uploaded=0 client_lastip=127.0.0.1";
+
+        #[test]
+        fn test() {
+            let result = ClientDatabaseInfo::from_query(TEST_STRING).unwrap();
+            assert_eq!(
+                result.client_unique_identifier(),
+                "Nbzbqqq6e1Ux1+3S/C4a0Qz9Q4k="
+            );
+            assert_eq!(result.client_total_connections(), 12);
+            assert_eq!(result.client_description(), None);
+            assert_eq!(result.client_last_ip(), Some("127.0.0.1"));
+        }
+
+        #[test]
+        fn test_db_entry() {
+            let entries = "count=2 cldbid=1 client_unique_identifier=serveradmin client_nickname=ServerAdmin client_created=1700000000 client_lastconnected=1700001000 client_totalconnections=0 client_description client_lastip|cldbid=2 client_unique_identifier=Nbzbqqq6e1Ux1+3S\\/C4a0Qz9Q4k= client_nickname=user client_created=1700000000 client_lastconnected=1700002000 client_totalconnections=3 client_description client_lastip=127.0.0.1"
+                .split('|')
+                .map(ClientDbEntry::from_query)
+                .collect::<anyhow::Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(entries[0].cldbid(), 1);
+            assert_eq!(entries[1].client_last_connected(), 1700002000);
+        }
+    }
 }
 
 pub mod client_query_result {
@@ -473,6 +2281,35 @@ pub mod client_query_result {
     }
 
     impl FromQueryString for DatabaseId {}
+
+    /// `clientgetuidfromclid`
+    #[derive(Clone, Debug, Deserialize)]
+    pub struct ClientUid {
+        #[serde(rename = "cluid")]
+        client_unique_id: String,
+    }
+
+    impl ClientUid {
+        pub fn client_unique_id(&self) -> &str {
+            &self.client_unique_id
+        }
+    }
+
+    impl FromQueryString for ClientUid {}
+
+    /// `clientgetnamefromdbid`
+    #[derive(Clone, Debug, Deserialize)]
+    pub struct ClientName {
+        name: String,
+    }
+
+    impl ClientName {
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    impl FromQueryString for ClientName {}
 }
 
 pub mod ban_entry {
@@ -485,11 +2322,19 @@ pub mod ban_entry {
         #[serde(rename = "banid")]
         ban_id: i64,
         #[serde(default)]
-        ip: String,
+        ip: Option<String>,
         #[serde(default)]
-        reason: String,
-        /*#[serde(rename = "invokercldbid", default)]
-        invoker_client_database_id: i64,*/
+        uid: Option<String>,
+        #[serde(default)]
+        reason: Option<String>,
+        /// Unix timestamp
+        #[serde(default)]
+        created: i64,
+        /// In seconds, 0 means permanent
+        #[serde(default)]
+        duration: u64,
+        #[serde(rename = "invokercldbid", default)]
+        invoker_client_database_id: i64,
         #[serde(rename = "invokername", default)]
         invoker_name: String,
         #[serde(rename = "invokeruid", default)]
@@ -503,14 +2348,31 @@ pub mod ban_entry {
             self.ban_id
         }
         pub fn ip(&self) -> &str {
-            &self.ip
+            self.ip.as_deref().unwrap_or_default()
+        }
+        #[allow(dead_code)]
+        pub fn uid(&self) -> &str {
+            self.uid.as_deref().unwrap_or_default()
         }
         pub fn reason(&self) -> &str {
-            &self.reason
+            self.reason.as_deref().unwrap_or_default()
+        }
+        #[allow(dead_code)]
+        pub fn created(&self) -> i64 {
+            self.created
         }
-        /*pub fn invoker_client_database_id(&self) -> i64 {
+        #[allow(dead_code)]
+        pub fn duration(&self) -> u64 {
+            self.duration
+        }
+        /// Permanent ban never expire
+        #[allow(dead_code)]
+        pub fn expired_at(&self, now: i64) -> bool {
+            self.duration != 0 && self.created + self.duration as i64 <= now
+        }
+        pub fn invoker_client_database_id(&self) -> i64 {
             self.invoker_client_database_id
-        }*/
+        }
         pub fn invoker_name(&self) -> &str {
             &self.invoker_name
         }
@@ -519,6 +2381,8 @@ pub mod ban_entry {
         }
     }
 
+    impl FromQueryString for BanEntry {}
+
     impl Display for BanEntry {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             write!(
@@ -532,44 +2396,200 @@ pub mod ban_entry {
         }
     }
 
+    #[derive(Clone, Copy, Debug, Deserialize)]
+    pub struct CreatedBan {
+        #[serde(rename = "banid")]
+        ban_id: i64,
+    }
+
+    impl CreatedBan {
+        pub fn ban_id(&self) -> i64 {
+            self.ban_id
+        }
+    }
+
+    impl FromQueryString for CreatedBan {}
+
     #[cfg(test)]
     mod test {
         use super::BanEntry;
         use super::FromQueryString;
 
-        const TEST_STRING: &str = r#"banid=5 ip name uid=953jm1Ez3CvbAx7FKzb19zAQm48= mytsid
-        lastnickname=باب created=1541834015 duration=0 invokername=AdminUser invokercldbid=2
-        invokeruid=QuietTeamspeak= reason enforcements=0|banid=6 ip=1.1.1.1
-        name uid mytsid lastnickname=باب created=1541834015 duration=0 invokername=AdminUser
-        invokercldbid=2 invokeruid=QuietTeamspeak= reason enforcements=0|banid=12
-        ip=114.5.1.4 name uid=+1145141919810 mytsid
-        lastnickname=!\s\s\s\s\s\s\s\s\s\s\s\s\s\sValidname created=1549729305 duration=0
-        invokername=AdminUser invokercldbid=2 invokeruid=QuietTeamspeak=
-        reason=Spam enforcements=0"#;
+        const TEST_STRING: &str = r#"banid=5 ip name uid=953jm1Ez3CvbAx7FKzb19zAQm48= mytsid lastnickname=باب created=1541834015 duration=0 invokername=AdminUser invokercldbid=2 invokeruid=QuietTeamspeak= reason enforcements=0|banid=6 ip=1.1.1.1 name uid mytsid lastnickname=باب created=1541834015 duration=0 invokername=AdminUser invokercldbid=2 invokeruid=QuietTeamspeak= reason enforcements=0|banid=12 ip=114.5.1.4 name uid=+1145141919810 mytsid lastnickname=!\s\s\s\s\s\s\s\s\s\s\s\s\s\sValidname created=1549729305 duration=0 invokername=AdminUser invokercldbid=2 invokeruid=QuietTeamspeak= reason=Spam enforcements=0"#;
 
         #[test]
         fn test() {
-            TEST_STRING
+            let entries = TEST_STRING
                 .split('|')
-                .map(|s| BanEntry::from_query(s))
-                .for_each(|entry| drop(entry));
+                .map(BanEntry::from_query)
+                .collect::<anyhow::Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(entries.len(), 3);
+            assert_eq!(entries[0].uid(), "953jm1Ez3CvbAx7FKzb19zAQm48=");
+            assert_eq!(entries[1].ip(), "1.1.1.1");
+            assert_eq!(entries[2].reason(), "Spam");
+            assert_eq!(entries[2].created(), 1549729305);
+            assert_eq!(entries[2].duration(), 0);
+            assert!(!entries[2].expired_at(i64::MAX));
         }
     }
+}
 
-    impl FromQueryString for BanEntry {}
+pub mod file_info {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    /// `ftgetfileinfo` entry
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct FileInfo {
+        cid: i64,
+        name: String,
+        /// In bytes
+        #[serde(default)]
+        size: u64,
+        /// Unix timestamp of last modification
+        #[serde(default)]
+        datetime: i64,
+    }
+
+    impl FileInfo {
+        #[allow(dead_code)]
+        pub fn cid(&self) -> i64 {
+            self.cid
+        }
+        #[allow(dead_code)]
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+        #[allow(dead_code)]
+        pub fn size(&self) -> u64 {
+            self.size
+        }
+        #[allow(dead_code)]
+        pub fn datetime(&self) -> i64 {
+            self.datetime
+        }
+    }
+
+    impl FromQueryString for FileInfo {}
+
+    #[cfg(test)]
+    mod test {
+        use super::FileInfo;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "cid=2 name=\\/icons\\/logo.png size=2048 datetime=1700000000";
+
+        #[test]
+        fn test() {
+            let result = FileInfo::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.name(), "/icons/logo.png");
+            assert_eq!(result.size(), 2048);
+        }
+    }
+}
+
+pub mod version {
+    use super::FromQueryString;
+    use serde::Deserialize;
+    use std::fmt::{Display, Formatter};
+
+    /// Result of `version`, fields are returned on a single line
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct VersionInfo {
+        version: String,
+        /// Unix timestamp of build, newer server has larger value
+        build: u64,
+        platform: String,
+    }
+
+    impl VersionInfo {
+        #[allow(dead_code)]
+        pub fn version(&self) -> &str {
+            &self.version
+        }
+        #[allow(dead_code)]
+        pub fn build(&self) -> u64 {
+            self.build
+        }
+        #[allow(dead_code)]
+        pub fn platform(&self) -> &str {
+            &self.platform
+        }
+        #[allow(dead_code)]
+        pub fn at_least_build(&self, build: u64) -> bool {
+            self.build >= build
+        }
+    }
+
+    impl Display for VersionInfo {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "{} (build {}) on {}",
+                self.version, self.build, self.platform
+            )
+        }
+    }
+
+    impl FromQueryString for VersionInfo {}
+
+    #[cfg(test)]
+    mod test {
+        use super::VersionInfo;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "version=3.13.7 build=1655727713 platform=Linux";
+
+        #[test]
+        fn test() {
+            let result = VersionInfo::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.version(), "3.13.7");
+            assert!(result.at_least_build(1655727713));
+            assert_eq!(result.to_string(), "3.13.7 (build 1655727713) on Linux");
+        }
+    }
+}
+
+pub mod server_log {
+    /// `loglevel` of `logadd`
+    #[allow(dead_code)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum LogLevel {
+        Error,
+        Warning,
+        Debug,
+        Info,
+    }
+
+    impl LogLevel {
+        pub fn value(&self) -> i64 {
+            match self {
+                Self::Error => 1,
+                Self::Warning => 2,
+                Self::Debug => 3,
+                Self::Info => 4,
+            }
+        }
+    }
 }
 
 mod status_result {
     use crate::types::QueryStatus;
     use anyhow::Error;
     use std::fmt::{Display, Formatter};
+    use std::time::Duration;
 
     pub type QueryResult<T> = Result<T, QueryError>;
 
+    /// Used if server doesn't tell how long the flood ban lasts
+    const DEFAULT_FLOOD_BAN_DELAY: Duration = Duration::from_secs(60);
+
     #[derive(Clone, Default, Debug)]
     pub struct QueryError {
         code: i32,
         message: String,
+        extra_message: Option<String>,
     }
 
     impl QueryError {
@@ -577,16 +2597,41 @@ mod status_result {
             Self {
                 code: -1,
                 message: "Expect result but none found.".to_string(),
+                extra_message: None,
             }
         }
         pub fn code(&self) -> i32 {
             self.code
         }
+        /// Server return error id 772 (channel_not_empty) when deleting channel with clients.
+        #[allow(dead_code)]
+        pub fn is_channel_not_empty(&self) -> bool {
+            self.code == 772
+        }
+        /// Server return error id 3329 when banned by anti-flood, `extra_msg` tells when to
+        /// retry (`you may retry in 600 seconds`). `None` if it is other error.
+        pub fn flood_ban_delay(&self) -> Option<Duration> {
+            if self.code != 3329 {
+                return None;
+            }
+            let seconds = self.extra_message.as_deref().and_then(|msg| {
+                let words = msg.split_whitespace().collect::<Vec<_>>();
+                words
+                    .windows(2)
+                    .find(|w| w[1].starts_with("second"))
+                    .and_then(|w| w[0].parse().ok())
+            });
+            Some(seconds.map_or(DEFAULT_FLOOD_BAN_DELAY, Duration::from_secs))
+        }
     }
 
     impl Display for QueryError {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{}({})", self.message, self.code)
+            write!(f, "{}({})", self.message, self.code)?;
+            if let Some(extra_message) = &self.extra_message {
+                write!(f, " {extra_message}")?;
+            }
+            Ok(())
         }
     }
 
@@ -597,6 +2642,7 @@ mod status_result {
             Self {
                 code: status.id(),
                 message: status.msg().clone(),
+                extra_message: status.extra_msg().map(ToString::to_string),
             }
         }
     }
@@ -606,9 +2652,36 @@ mod status_result {
             Self {
                 code: -2,
                 message: s.to_string(),
+                extra_message: None,
             }
         }
     }
+
+    #[cfg(test)]
+    mod test {
+        use super::QueryError;
+        use crate::types::QueryStatus;
+        use std::time::Duration;
+
+        #[test]
+        fn test_flood_ban_delay() {
+            let status = QueryStatus::try_from(
+                "error id=3329 msg=connection\\sfailed,\\syou\\sare\\sbanned extra_msg=you\\smay\\sretry\\sin\\s600\\sseconds",
+            )
+            .unwrap();
+            let error = QueryError::from(status);
+            assert_eq!(error.flood_ban_delay(), Some(Duration::from_secs(600)));
+
+            let status = QueryStatus::try_from("error id=3329 msg=banned").unwrap();
+            assert_eq!(
+                QueryError::from(status).flood_ban_delay(),
+                Some(Duration::from_secs(60))
+            );
+            let status =
+                QueryStatus::try_from("error id=1281 msg=database\\sempty\\sresult\\sset").unwrap();
+            assert_eq!(QueryError::from(status).flood_ban_delay(), None);
+        }
+    }
 }
 
 mod client_info {
@@ -630,6 +2703,10 @@ mod client_info {
         //client_unique_identifier: String,
         client_away: bool,
         client_idle_time: i64,
+        #[serde(default)]
+        connection_connected_time: i64,
+        #[serde(default)]
+        client_myteamspeak_id: Option<String>,
     }
 
     impl ClientInfo {
@@ -641,11 +2718,112 @@ mod client_info {
                 || !self.client_input_hardware
                 || self.client_idle_time / 1000 > 300
         }
+
+        /// In milliseconds
+        pub fn connection_connected_time(&self) -> i64 {
+            self.connection_connected_time
+        }
+        /// `None` if client has no MyTeamSpeak account linked
+        pub fn client_myteamspeak_id(&self) -> Option<&str> {
+            self.client_myteamspeak_id
+                .as_deref()
+                .filter(|id| !id.is_empty())
+        }
     }
 
     impl FromQueryString for ClientInfo {}
 }
 
+pub mod connection_stats {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    /// `connection_*` fields of `clientinfo`
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ConnectionStats {
+        /// In milliseconds
+        #[serde(rename = "connection_ping", default)]
+        ping: f64,
+        #[serde(rename = "connection_ping_deviation", default)]
+        ping_deviation: f64,
+        #[serde(rename = "connection_bandwidth_sent_last_second_total")]
+        bandwidth_sent_last_second: u64,
+        #[serde(rename = "connection_bandwidth_received_last_second_total")]
+        bandwidth_received_last_second: u64,
+        /// In milliseconds
+        #[serde(rename = "connection_connected_time")]
+        connected_time: i64,
+    }
+
+    impl ConnectionStats {
+        #[allow(dead_code)]
+        pub fn ping(&self) -> f64 {
+            self.ping
+        }
+        #[allow(dead_code)]
+        pub fn ping_deviation(&self) -> f64 {
+            self.ping_deviation
+        }
+        #[allow(dead_code)]
+        pub fn bandwidth_sent_last_second(&self) -> u64 {
+            self.bandwidth_sent_last_second
+        }
+        #[allow(dead_code)]
+        pub fn bandwidth_received_last_second(&self) -> u64 {
+            self.bandwidth_received_last_second
+        }
+        #[allow(dead_code)]
+        pub fn connected_time(&self) -> i64 {
+            self.connected_time
+        }
+    }
+
+    impl FromQueryString for ConnectionStats {}
+
+    #[cfg(test)]
+    mod test {
+        use super::ConnectionStats;
+        use crate::types::FromQueryString;
+
+        const TEST_STRING: &str = "cid=1 client_idle_time=1234 client_nickname=user connection_filetransfer_bandwidth_sent=0 connection_bandwidth_sent_last_second_total=81 connection_bandwidth_received_last_second_total=83 connection_connected_time=61000 connection_ping=12.3456 connection_ping_deviation=1.2";
+
+        #[test]
+        fn test() {
+            let result = ConnectionStats::from_query(TEST_STRING).unwrap();
+            assert_eq!(result.ping(), 12.3456);
+            assert_eq!(result.bandwidth_received_last_second(), 83);
+            assert_eq!(result.connected_time(), 61000);
+        }
+    }
+}
+
+pub mod client_bandwidth {
+    use super::FromQueryString;
+    use serde::Deserialize;
+
+    /// Bytes per second in last second
+    #[derive(Clone, Debug, Default, Deserialize)]
+    pub struct ClientBandwidth {
+        #[serde(rename = "connection_bandwidth_sent_last_second_total")]
+        sent: u64,
+        #[serde(rename = "connection_bandwidth_received_last_second_total")]
+        received: u64,
+    }
+
+    impl ClientBandwidth {
+        #[allow(dead_code)]
+        pub fn sent(&self) -> u64 {
+            self.sent
+        }
+        #[allow(dead_code)]
+        pub fn received(&self) -> u64 {
+            self.received
+        }
+    }
+
+    impl FromQueryString for ClientBandwidth {}
+}
+
 mod pseudo_event_helper {
     use async_trait::async_trait;
 
@@ -954,24 +3132,47 @@ mod arg {
     }
 }
 
-pub use ban_entry::BanEntry;
-pub use channel::Channel;
+pub use ban_entry::{BanEntry, CreatedBan};
+pub use channel::{
+    Channel, ChannelWithDescription, ChannelWithFlags, ChannelWithLimits, ChannelWithTopic,
+    ChannelWithVoice,
+};
+pub use channel_info::{ChannelInfo, ChannelMatch, ChannelName};
 pub use client::Client;
+pub use client_badges::ClientWithBadges;
+pub use client_bandwidth::ClientBandwidth;
+pub use client_database_info::{ClientDatabaseInfo, ClientDbEntry};
 pub use client_info::ClientInfo;
-pub use client_query_result::DatabaseId;
+pub use client_ip::ClientWithIp;
+pub use client_query_result::{ClientName, ClientUid, DatabaseId};
+pub use connection_stats::ConnectionStats;
 pub use create_channel::CreateChannel;
+pub use dynamic_client::DynamicClient;
+pub use group::{
+    ChannelGroupMember, ClientGroupMembership, CreatedChannelGroup, ServerGroup, ServerGroupClient,
+    ServerGroupId, ServerGroupMember, ServerGroupType,
+};
+pub use host_info::HostInfo;
+pub use instance_settings::{DefaultGroups, InstanceSettings};
 pub use notifies::{
-    NotifyClientEnterView, NotifyClientLeftView, NotifyClientMovedView, NotifyTextMessage,
+    ChannelDeletedEvent, NotifyClientEnterView, NotifyClientLeftView, NotifyClientMovedView,
+    NotifyTextMessage,
 };
 pub use pseudo_event_helper::EventHelperTrait;
 
 pub use arg::ArgPass2Controller;
+pub use file_info::FileInfo;
+pub use permission::{NamedPermission, Permission, PermissionDefinition};
 #[cfg(not(feature = "tracker"))]
 pub use pseudo_event_helper::PseudoEventHelper;
 pub use query_status::QueryStatus;
+pub use self_info::SelfInfo;
 use serde::Deserialize;
-pub use server_info::ServerInfo;
+pub use server_info::{HostMessageMode, QuotaInfo, ServerInfo, TransferQuotas};
+pub use server_log::LogLevel;
 pub use status_result::{QueryError, QueryResult};
 pub use to_map::ToNameMap;
 pub use user_state::{ConfigMappedUserState, SafeUserState};
+pub use version::VersionInfo;
+pub use virtual_server::VirtualServerWithStats;
 pub use whoami::WhoAmI;